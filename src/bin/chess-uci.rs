@@ -0,0 +1,236 @@
+#![allow(clippy::needless_return)]
+
+// chess-uci: the engine, speaking the Universal Chess Interface over stdin and stdout
+
+use std::io::BufRead;
+use std::sync::Arc;
+use std::sync::Mutex;
+use std::sync::atomic::AtomicBool;
+use std::sync::atomic::Ordering;
+use std::thread::JoinHandle;
+use std::time::Duration;
+
+use chess::components::chess_board::ChessBoard;
+use chess::components::color::Color;
+use chess::engine::search::MATE_SCORE;
+use chess::engine::search::MAX_DEPTH;
+use chess::engine::search::SearchInfo;
+use chess::engine::search::SearchLimits;
+use chess::engine::search::search;
+use chess::engine::transposition::DEFAULT_MEGABYTES;
+use chess::engine::transposition::TranspositionTable;
+use chess::error::ChessError;
+use chess::notation::coordinate::parse_coordinate;
+use chess::notation::coordinate::to_coordinate;
+use chess::utils::logs::set_logs_echoed;
+use chess::utils::logs::with_logs_muted;
+
+const MAX_HASH_MB: u64 = 1024;
+// kept back from the clock for the time it takes to send the move
+const MOVE_OVERHEAD: Duration = Duration::from_millis(30);
+
+
+fn main() {
+    // stdout belongs to the protocol
+    set_logs_echoed(false);
+
+    let mut engine = UciEngine::default();
+    for line in std::io::stdin().lock().lines() {
+        let line = match line {
+            Ok(line) => line,
+            Err(_) => break,
+        };
+        if !engine.handle_command(&line) {
+            break;
+        }
+    }
+    engine.stop_search();
+}
+
+
+struct UciEngine {
+    chess_board: ChessBoard,
+    // shared with the search thread, and kept between searches
+    transposition_table: Arc<Mutex<TranspositionTable>>,
+    search_thread: Option<(JoinHandle<()>, Arc<AtomicBool>)>,
+}
+
+
+impl Default for UciEngine {
+
+    fn default() -> Self {
+        Self {
+            chess_board: ChessBoard::default(),
+            transposition_table: Arc::new(Mutex::new(TranspositionTable::default())),
+            search_thread: None,
+        }
+    }
+
+}
+
+
+impl UciEngine {
+
+    // act on one line from the GUI; false once it's time to quit
+    fn handle_command(&mut self, line: &str) -> bool {
+        let words: Vec<&str> = line.split_whitespace().collect();
+        match words.first() {
+            Some(&"uci") => {
+                println!("id name chess {}", env!("CARGO_PKG_VERSION"));
+                println!("id author Rue Starsja");
+                println!("option name Hash type spin default {} min 1 max {}", DEFAULT_MEGABYTES, MAX_HASH_MB);
+                println!("uciok");
+            },
+            Some(&"isready") => println!("readyok"),
+            Some(&"setoption") => {
+                self.stop_search();
+                self.set_option(&words[1..]);
+            },
+            Some(&"ucinewgame") => {
+                self.stop_search();
+                self.chess_board = ChessBoard::default();
+                self.transposition_table.lock().unwrap().clear();
+            },
+            Some(&"position") => {
+                self.stop_search();
+                match Self::read_position(&words[1..]) {
+                    Ok(chess_board) => self.chess_board = chess_board,
+                    Err(error) => println!("info string {}", error),
+                }
+            },
+            Some(&"go") => {
+                self.stop_search();
+                self.start_search(&words[1..]);
+            },
+            Some(&"stop") => self.stop_search(),
+            Some(&"quit") => return false,
+            Some(command) => println!("info string Unknown command \"{}\".", command),
+            None => (),
+        }
+        return true;
+    }
+
+    // e.g. "name Hash value 64"
+    fn set_option(&mut self, words: &[&str]) {
+        let name_index = words.iter().position(|word| *word == "name");
+        let value_index = words.iter().position(|word| *word == "value").unwrap_or(words.len());
+        let name_index = match name_index {
+            Some(name_index) if name_index < value_index => name_index,
+            _ => {
+                println!("info string setoption needs \"name <id>\" before any \"value <x>\".");
+                return;
+            },
+        };
+        let name = words[name_index + 1..value_index].join(" ");
+        let value = words.get(value_index + 1..).unwrap_or_default().join(" ");
+        match name.to_lowercase().as_str() {
+            "hash" => match value.parse::<u64>() {
+                Ok(megabytes) => {
+                    let megabytes = megabytes.clamp(1, MAX_HASH_MB);
+                    *self.transposition_table.lock().unwrap() = TranspositionTable::construct(megabytes);
+                    println!("info string Hash set to {} MB.", megabytes);
+                },
+                Err(_) => println!("info string Hash needs a size in megabytes, not \"{}\".", value),
+            },
+            _ => println!("info string Unknown option \"{}\".", name),
+        }
+    }
+
+    // "startpos" or "fen <FEN>", then optionally "moves" and the moves played from there in coordinate notation
+    fn read_position(words: &[&str]) -> Result<ChessBoard, ChessError> {
+        let moves_index = words.iter().position(|word| *word == "moves").unwrap_or(words.len());
+        let mut chess_board = match words.first() {
+            Some(&"startpos") => ChessBoard::default(),
+            Some(&"fen") => ChessBoard::from_fen(&words[1..moves_index].join(" "))?,
+            _ => return Err(ChessError::Parse(String::from("position needs \"startpos\" or \"fen\"."))),
+        };
+        for coordinate in words.iter().skip(moves_index + 1) {
+            let chess_move = parse_coordinate(&chess_board, coordinate)?;
+            with_logs_muted(|| chess_board.make_move(&chess_move));
+        }
+        return Ok(chess_board);
+    }
+
+    // "depth N", "movetime MS", "wtime MS btime MS [winc MS binc MS movestogo N]" or "infinite"
+    fn read_limits(&self, words: &[&str]) -> SearchLimits {
+        let get_number = |name: &str| -> Option<u64> {
+            let index = words.iter().position(|word| *word == name)?;
+            words.get(index + 1)?.parse().ok()
+        };
+        let mut limits = SearchLimits { max_depth: MAX_DEPTH, time_limit: None };
+        if let Some(depth) = get_number("depth") {
+            // anything deeper than the search goes is the same as its deepest, rather than wrapping around
+            limits.max_depth = depth.min(MAX_DEPTH as u64) as u32;
+        }
+        if let Some(movetime) = get_number("movetime") {
+            limits.time_limit = Some(Duration::from_millis(movetime));
+        }
+        let (time_name, increment_name) = match self.chess_board.get_turn() {
+            Color::White => ("wtime", "winc"),
+            Color::Black => ("btime", "binc"),
+        };
+        if let Some(time_left) = get_number(time_name) {
+            // spread the clock over the moves left, assuming a long game when not told
+            let moves_to_go = get_number("movestogo").unwrap_or(30).max(1);
+            let increment = get_number(increment_name).unwrap_or(0);
+            let budget = Duration::from_millis(time_left / moves_to_go + increment / 2);
+            let most = Duration::from_millis(time_left).saturating_sub(MOVE_OVERHEAD);
+            limits.time_limit = Some(budget.min(most).max(Duration::from_millis(1)));
+        }
+        if words.contains(&"infinite") {
+            limits = SearchLimits { max_depth: MAX_DEPTH, time_limit: None };
+        }
+        return limits;
+    }
+
+    // search on another thread, so that "stop" can still be read meanwhile
+    fn start_search(&mut self, words: &[&str]) {
+        let limits = self.read_limits(words);
+        let chess_board = self.chess_board.clone();
+        let stop = Arc::new(AtomicBool::new(false));
+        let thread_stop = Arc::clone(&stop);
+        let transposition_table = Arc::clone(&self.transposition_table);
+        let handle = std::thread::spawn(move || {
+            let transposition_table = &mut transposition_table.lock().unwrap();
+            let best_move = search(&chess_board, limits, transposition_table, &thread_stop, |info| println!("{}", get_info_line(info)));
+            match best_move {
+                Some(chess_move) => println!("bestmove {}", to_coordinate(&chess_move)),
+                // there is nothing to play
+                None => println!("bestmove 0000"),
+            }
+        });
+        self.search_thread = Some((handle, stop));
+    }
+
+    // stop any search, waiting for it to send its best move
+    fn stop_search(&mut self) {
+        if let Some((handle, stop)) = self.search_thread.take() {
+            stop.store(true, Ordering::Relaxed);
+            let _ = handle.join();
+        }
+    }
+
+}
+
+
+// e.g. "info depth 4 score cp 35 nodes 5120 nps 20480 time 250 pv e2e4"
+fn get_info_line(info: &SearchInfo) -> String {
+    let score = if info.score.abs() >= MATE_SCORE - MAX_DEPTH as i32 {
+        // in moves rather than plies, negative when being mated
+        let plies = MATE_SCORE - info.score.abs();
+        let moves = (plies + 1) / 2;
+        format!("mate {}", if info.score > 0 { moves } else { -moves })
+    } else {
+        format!("cp {}", info.score)
+    };
+    let milliseconds = info.elapsed.as_millis().max(1);
+    return format!(
+        "info depth {} score {} nodes {} nps {} time {} pv {}",
+        info.depth,
+        score,
+        info.nodes,
+        info.nodes as u128 * 1000 / milliseconds,
+        info.elapsed.as_millis(),
+        to_coordinate(&info.best_move)
+    );
+}
@@ -0,0 +1,36 @@
+// scripted-engine: a stand-in UCI engine for tests, answering each "go" with the next of a fixed list of moves
+//
+// The moves come from the arguments, or else from the space-separated SCRIPTED_ENGINE_MOVES environment variable.
+// Positions are ignored, a "-" in the list means no answer at all to that "go", and once the list runs out
+// the engine answers "bestmove 0000".
+
+use std::io::BufRead;
+
+
+fn main() {
+    let mut moves: Vec<String> = std::env::args().skip(1).collect();
+    if moves.is_empty() {
+        moves = std::env::var("SCRIPTED_ENGINE_MOVES")
+            .unwrap_or_default()
+            .split_whitespace()
+            .map(String::from)
+            .collect();
+    }
+    let mut moves = moves.into_iter();
+
+    for line in std::io::stdin().lock().lines().map_while(Result::ok) {
+        match line.split_whitespace().next() {
+            Some("uci") => {
+                println!("id name Scripted engine");
+                println!("uciok");
+            },
+            Some("isready") => println!("readyok"),
+            Some("go") => match moves.next() {
+                Some(chess_move) if chess_move == "-" => (),
+                chess_move => println!("bestmove {}", chess_move.unwrap_or(String::from("0000"))),
+            },
+            Some("quit") => break,
+            _ => (),
+        }
+    }
+}
@@ -0,0 +1,233 @@
+// crate::cli
+
+use std::fmt::Display;
+use std::path::PathBuf;
+use std::str::FromStr;
+use std::time::Duration;
+
+use chess::components::color::Color;
+use chess::engine::search::SearchLimits;
+use chess::error::ChessError;
+use chess::utils::logs::DEFAULT_LOG_PATH;
+
+pub const USAGE: &str = "Usage: chess [OPTIONS] [FEN | PGN FILE]
+
+Options:
+  --gui                    Play in a window (the default when built with the \"gui\" feature)
+  --tui, --cli             Play in the terminal
+  --fen <FEN>              Start from this position
+  --pgn <FILE>             Load the games in this PGN file, starting at the first one
+  --opponent <PLAYER>      Who plays the other side: human (default) or engine
+  --side <COLOR>           The side you play against the engine: white (default) or black
+  --white <PLAYER>         Who plays white: human or engine (overrides --opponent and --side)
+  --black <PLAYER>         Who plays black: human or engine (overrides --opponent and --side)
+  --engine-time <MS>       How long the engine thinks about each move [default: 1000]
+  --engine-depth <PLIES>   How many moves ahead the engine looks at most
+  --engine-path <FILE>     Play the engine's moves with this UCI engine instead of the built-in one
+  --log <FILE>             Where to write the log [default: logs/latest.log]
+  --resources <DIR>        Where the sprites are found [default: resources/, next to the binary]
+  -h, --help               Print this help";
+
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Mode {
+    Gui,
+    Terminal,
+}
+
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Player {
+    Human,
+    Engine,
+}
+
+
+impl Display for Player {
+
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Human => write!(f, "human"),
+            Self::Engine => write!(f, "engine"),
+        }
+    }
+
+}
+
+
+impl Player {
+
+    fn from_label(label: &str) -> Result<Self, ChessError> {
+        match label {
+            "human" => Ok(Self::Human),
+            "engine" => Ok(Self::Engine),
+            other => Err(ChessError::Parse(format!("Unknown player \"{}\"; expected human or engine.", other))),
+        }
+    }
+
+}
+
+
+// Who plays each side
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct Players {
+    pub white: Player,
+    pub black: Player,
+}
+
+
+impl Players {
+
+    pub fn get(&self, color: Color) -> Player {
+        match color {
+            Color::White => self.white,
+            Color::Black => self.black,
+        }
+    }
+
+    pub fn set(&mut self, color: Color, player: Player) {
+        match color {
+            Color::White => self.white = player,
+            Color::Black => self.black = player,
+        }
+    }
+
+}
+
+
+// Everything the chess binary can be told on its command line
+#[derive(Debug)]
+pub struct Options {
+    pub mode: Mode,
+    pub fen: Option<String>,
+    pub pgn_path: Option<PathBuf>,
+    pub players: Players,
+    pub engine_limits: SearchLimits,
+    pub engine_path: Option<PathBuf>,
+    pub log_path: PathBuf,
+    pub resource_dir: Option<PathBuf>,
+    pub show_help: bool,
+}
+
+
+impl Default for Options {
+
+    fn default() -> Self {
+        Self {
+            mode: if cfg!(feature = "gui") { Mode::Gui } else { Mode::Terminal },
+            fen: None,
+            pgn_path: None,
+            players: Players { white: Player::Human, black: Player::Human },
+            engine_limits: SearchLimits::default(),
+            engine_path: None,
+            log_path: PathBuf::from(DEFAULT_LOG_PATH),
+            resource_dir: None,
+            show_help: false,
+        }
+    }
+
+}
+
+
+impl Options {
+
+    // Reads the arguments after the program name; a bare argument is a PGN file if it ends in ".pgn", otherwise a FEN
+    pub fn parse(args: impl IntoIterator<Item = String>) -> Result<Self, ChessError> {
+        let mut options = Self::default();
+        let mut opponent = Player::Human;
+        let mut side = Color::White;
+        let mut white: Option<Player> = None;
+        let mut black: Option<Player> = None;
+        let mut args = args.into_iter();
+        while let Some(arg) = args.next() {
+            match arg.as_str() {
+                "-h" | "--help" => options.show_help = true,
+                "--gui" if cfg!(feature = "gui") => options.mode = Mode::Gui,
+                "--gui" => return Err(ChessError::Parse(String::from("This build has no GUI; rebuild it with the \"gui\" feature."))),
+                "--tui" | "--cli" => options.mode = Mode::Terminal,
+                "--fen" => options.fen = Some(Self::get_value(&arg, args.next())?),
+                "--pgn" => options.pgn_path = Some(PathBuf::from(Self::get_value(&arg, args.next())?)),
+                "--opponent" => opponent = Player::from_label(&Self::get_value(&arg, args.next())?)?,
+                "--white" => white = Some(Player::from_label(&Self::get_value(&arg, args.next())?)?),
+                "--black" => black = Some(Player::from_label(&Self::get_value(&arg, args.next())?)?),
+                "--engine-time" => {
+                    let milliseconds = Self::get_number(&arg, args.next())?;
+                    options.engine_limits.time_limit = Some(Duration::from_millis(milliseconds));
+                },
+                "--engine-depth" => options.engine_limits.max_depth = Self::get_number(&arg, args.next())?,
+                "--engine-path" => options.engine_path = Some(PathBuf::from(Self::get_value(&arg, args.next())?)),
+                "--side" => {
+                    side = match Self::get_value(&arg, args.next())?.as_str() {
+                        "white" => Color::White,
+                        "black" => Color::Black,
+                        other => return Err(ChessError::Parse(format!("Unknown side \"{}\"; expected white or black.", other))),
+                    };
+                },
+                "--log" => options.log_path = PathBuf::from(Self::get_value(&arg, args.next())?),
+                "--resources" => options.resource_dir = Some(PathBuf::from(Self::get_value(&arg, args.next())?)),
+                _ if arg.starts_with('-') => return Err(ChessError::Parse(format!("Unknown option \"{}\".", arg))),
+                _ if arg.ends_with(".pgn") => options.pgn_path = Some(PathBuf::from(arg)),
+                _ => options.fen = Some(arg),
+            }
+        }
+        options.players.set(side.opposite(), opponent);
+        if let Some(player) = white {
+            options.players.white = player;
+        }
+        if let Some(player) = black {
+            options.players.black = player;
+        }
+        if options.fen.is_some() && options.pgn_path.is_some() {
+            return Err(ChessError::Parse(String::from("Give either a FEN or a PGN file, not both.")));
+        }
+        return Ok(options);
+    }
+
+    fn get_value(option: &str, value: Option<String>) -> Result<String, ChessError> {
+        value.ok_or(ChessError::Parse(format!("{} needs a value.", option)))
+    }
+
+    fn get_number<T: FromStr>(option: &str, value: Option<String>) -> Result<T, ChessError> {
+        let value = Self::get_value(option, value)?;
+        value.parse().map_err(|_| ChessError::Parse(format!("{} needs a whole number, not \"{}\".", option, value)))
+    }
+
+}
+
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    fn parse(args: &[&str]) -> Result<Options, ChessError> {
+        Options::parse(args.iter().map(|arg| arg.to_string()))
+    }
+
+    #[test]
+    fn reads_options_and_bare_arguments() {
+        let options = parse(&["--tui", "--opponent", "engine", "--side", "black", "--log", "out/chess.log", "games/a.pgn"]).unwrap();
+        assert_eq!(options.mode, Mode::Terminal);
+        assert_eq!(options.players, Players { white: Player::Engine, black: Player::Human });
+        assert_eq!(options.log_path, PathBuf::from("out/chess.log"));
+        assert_eq!(options.pgn_path, Some(PathBuf::from("games/a.pgn")));
+        let options = parse(&["8/8/8/8/8/8/k6P/7K w - - 0 1"]).unwrap();
+        assert_eq!(options.fen.as_deref(), Some("8/8/8/8/8/8/k6P/7K w - - 0 1"));
+        assert_eq!(options.players, Players { white: Player::Human, black: Player::Human });
+        let options = parse(&["--white", "engine", "--black", "engine", "--engine-time", "200", "--engine-depth", "3"]).unwrap();
+        assert_eq!(options.players, Players { white: Player::Engine, black: Player::Engine });
+        assert_eq!(options.engine_limits, SearchLimits { max_depth: 3, time_limit: Some(Duration::from_millis(200)) });
+        assert_eq!(options.engine_path, None);
+    }
+
+    #[test]
+    fn rejects_bad_options() {
+        assert!(parse(&["--opponent", "computer"]).is_err());
+        assert!(parse(&["--side"]).is_err());
+        assert!(parse(&["--engine-time", "soon"]).is_err());
+        assert!(parse(&["--engine-depth", "4294967296"]).is_err());
+        assert!(parse(&["--fast"]).is_err());
+        assert!(parse(&["--fen", "8/8/8/8/8/8/8/8 w - - 0 1", "a.pgn"]).is_err());
+    }
+
+}
@@ -1,6 +1,18 @@
 // crate::components::chess_board
 
+use crate::components::chess_move::ChessMove;
+use crate::components::chess_move::MoveKind;
+use crate::components::chess_move::UndoRecord;
 use crate::components::chess_piece::ChessPiece;
+use crate::components::color::Color;
+use crate::components::game_outcome::GameOutcome;
+use crate::components::piece_type::PieceType;
+use crate::components::square::RANK_1;
+use crate::components::square::RANK_8;
+use crate::components::square::Square;
+use crate::components::zobrist;
+use crate::error::ChessError;
+use crate::notation::san::to_san;
 use crate::rules::bishop;
 use crate::rules::king;
 use crate::rules::knight;
@@ -8,12 +20,26 @@ use crate::rules::pawn;
 use crate::rules::queen;
 use crate::rules::rook;
 use crate::utils::logs::log;
+use crate::utils::logs::with_logs_muted;
 
 use std::fmt::Display;
 
 
+pub const PROMOTION_TYPES: [PieceType; 4] = [PieceType::Queen, PieceType::Rook, PieceType::Bishop, PieceType::Knight];
+
+// The start and target of the last move played
+pub type LastMove = Option<(Square, Square)>;
+
+
+#[derive(Clone)]
 pub struct ChessBoard {
     contents: [[ChessPiece; 8]; 8],
+    turn: Color,
+    last_move: LastMove,
+    halfmove_clock: u32,
+    fullmove_number: u32,
+    // Zobrist hash of the position, kept up to date as moves are made
+    hash: u64,
 }
 
 
@@ -21,19 +47,19 @@ impl Default for ChessBoard {
 
     fn default() -> Self {
         let no = ChessPiece::default();
-        let bp = ChessPiece::construct(String::from("black"), String::from("pawn"));
-        let br = ChessPiece::construct(String::from("black"), String::from("rook"));
-        let bn = ChessPiece::construct(String::from("black"), String::from("knight"));
-        let bb = ChessPiece::construct(String::from("black"), String::from("bishop"));
-        let bq = ChessPiece::construct(String::from("black"), String::from("queen"));
-        let bk = ChessPiece::construct(String::from("black"), String::from("king"));
-        let wp = ChessPiece::construct(String::from("white"), String::from("pawn"));
-        let wr = ChessPiece::construct(String::from("white"), String::from("rook"));
-        let wn = ChessPiece::construct(String::from("white"), String::from("knight"));
-        let wb = ChessPiece::construct(String::from("white"), String::from("bishop"));
-        let wq = ChessPiece::construct(String::from("white"), String::from("queen"));
-        let wk = ChessPiece::construct(String::from("white"), String::from("king"));
-        Self {
+        let bp = ChessPiece::construct(Color::Black, PieceType::Pawn);
+        let br = ChessPiece::construct(Color::Black, PieceType::Rook);
+        let bn = ChessPiece::construct(Color::Black, PieceType::Knight);
+        let bb = ChessPiece::construct(Color::Black, PieceType::Bishop);
+        let bq = ChessPiece::construct(Color::Black, PieceType::Queen);
+        let bk = ChessPiece::construct(Color::Black, PieceType::King);
+        let wp = ChessPiece::construct(Color::White, PieceType::Pawn);
+        let wr = ChessPiece::construct(Color::White, PieceType::Rook);
+        let wn = ChessPiece::construct(Color::White, PieceType::Knight);
+        let wb = ChessPiece::construct(Color::White, PieceType::Bishop);
+        let wq = ChessPiece::construct(Color::White, PieceType::Queen);
+        let wk = ChessPiece::construct(Color::White, PieceType::King);
+        let mut chess_board = Self {
             contents: [
                 [ br.clone(), bn.clone(), bb.clone(), bq.clone(), bk.clone(), bb.clone(), bn.clone(), br.clone() ],
                 [ bp.clone(), bp.clone(), bp.clone(), bp.clone(), bp.clone(), bp.clone(), bp.clone(), bp.clone() ],
@@ -43,8 +69,15 @@ impl Default for ChessBoard {
                 [ no.clone(), no.clone(), no.clone(), no.clone(), no.clone(), no.clone(), no.clone(), no.clone() ],
                 [ wp.clone(), wp.clone(), wp.clone(), wp.clone(), wp.clone(), wp.clone(), wp.clone(), wp.clone() ],
                 [ wr.clone(), wn.clone(), wb.clone(), wq.clone(), wk.clone(), wb.clone(), wn.clone(), wr.clone() ]
-            ]
-        }
+            ],
+            turn: Color::White,
+            last_move: None,
+            halfmove_clock: 0,
+            fullmove_number: 1,
+            hash: 0,
+        };
+        chess_board.hash = zobrist::compute_hash(&chess_board);
+        return chess_board;
     }
 
 }
@@ -65,111 +98,111 @@ impl Display for ChessBoard {
  {} | {} | {} | {} | {} | {} | {} | {} | {} | {}
  {} | {} | {} | {} | {} | {} | {} | {} | {} | {}
      {}   {}   {}   {}   {}   {}   {}   {}    ",
-            Self::get_file_label(0),
-            Self::get_file_label(1),
-            Self::get_file_label(2),
-            Self::get_file_label(3),
-            Self::get_file_label(4),
-            Self::get_file_label(5),
-            Self::get_file_label(6),
-            Self::get_file_label(7),
-
-            Self::get_rank_label(0),
-            self.borrow_space_contents(0, 0),
-            self.borrow_space_contents(0, 1),
-            self.borrow_space_contents(0, 2),
-            self.borrow_space_contents(0, 3),
-            self.borrow_space_contents(0, 4),
-            self.borrow_space_contents(0, 5),
-            self.borrow_space_contents(0, 6),
-            self.borrow_space_contents(0, 7),
-            Self::get_rank_label(0),
-
-            Self::get_rank_label(1),
-            self.borrow_space_contents(1, 0),
-            self.borrow_space_contents(1, 1),
-            self.borrow_space_contents(1, 2),
-            self.borrow_space_contents(1, 3),
-            self.borrow_space_contents(1, 4),
-            self.borrow_space_contents(1, 5),
-            self.borrow_space_contents(1, 6),
-            self.borrow_space_contents(1, 7),
-            Self::get_rank_label(1),
-
-            Self::get_rank_label(2),
-            self.borrow_space_contents(2, 0),
-            self.borrow_space_contents(2, 1),
-            self.borrow_space_contents(2, 2),
-            self.borrow_space_contents(2, 3),
-            self.borrow_space_contents(2, 4),
-            self.borrow_space_contents(2, 5),
-            self.borrow_space_contents(2, 6),
-            self.borrow_space_contents(2, 7),
-            Self::get_rank_label(2),
-
-            Self::get_rank_label(3),
-            self.borrow_space_contents(3, 0),
-            self.borrow_space_contents(3, 1),
-            self.borrow_space_contents(3, 2),
-            self.borrow_space_contents(3, 3),
-            self.borrow_space_contents(3, 4),
-            self.borrow_space_contents(3, 5),
-            self.borrow_space_contents(3, 6),
-            self.borrow_space_contents(3, 7),
-            Self::get_rank_label(3),
-
-            Self::get_rank_label(4),
-            self.borrow_space_contents(4, 0),
-            self.borrow_space_contents(4, 1),
-            self.borrow_space_contents(4, 2),
-            self.borrow_space_contents(4, 3),
-            self.borrow_space_contents(4, 4),
-            self.borrow_space_contents(4, 5),
-            self.borrow_space_contents(4, 6),
-            self.borrow_space_contents(4, 7),
-            Self::get_rank_label(4),
-
-            Self::get_rank_label(5),
-            self.borrow_space_contents(5, 0),
-            self.borrow_space_contents(5, 1),
-            self.borrow_space_contents(5, 2),
-            self.borrow_space_contents(5, 3),
-            self.borrow_space_contents(5, 4),
-            self.borrow_space_contents(5, 5),
-            self.borrow_space_contents(5, 6),
-            self.borrow_space_contents(5, 7),
-            Self::get_rank_label(5),
-
-            Self::get_rank_label(6),
-            self.borrow_space_contents(6, 0),
-            self.borrow_space_contents(6, 1),
-            self.borrow_space_contents(6, 2),
-            self.borrow_space_contents(6, 3),
-            self.borrow_space_contents(6, 4),
-            self.borrow_space_contents(6, 5),
-            self.borrow_space_contents(6, 6),
-            self.borrow_space_contents(6, 7),
-            Self::get_rank_label(6),
-
-            Self::get_rank_label(7),
-            self.borrow_space_contents(7, 0),
-            self.borrow_space_contents(7, 1),
-            self.borrow_space_contents(7, 2),
-            self.borrow_space_contents(7, 3),
-            self.borrow_space_contents(7, 4),
-            self.borrow_space_contents(7, 5),
-            self.borrow_space_contents(7, 6),
-            self.borrow_space_contents(7, 7),
-            Self::get_rank_label(7),
-
-            Self::get_file_label(0),
-            Self::get_file_label(1),
-            Self::get_file_label(2),
-            Self::get_file_label(3),
-            Self::get_file_label(4),
-            Self::get_file_label(5),
-            Self::get_file_label(6),
-            Self::get_file_label(7)
+            Square::get_file_label(0),
+            Square::get_file_label(1),
+            Square::get_file_label(2),
+            Square::get_file_label(3),
+            Square::get_file_label(4),
+            Square::get_file_label(5),
+            Square::get_file_label(6),
+            Square::get_file_label(7),
+
+            Square::get_rank_label(0),
+            self.contents[0][0],
+            self.contents[0][1],
+            self.contents[0][2],
+            self.contents[0][3],
+            self.contents[0][4],
+            self.contents[0][5],
+            self.contents[0][6],
+            self.contents[0][7],
+            Square::get_rank_label(0),
+
+            Square::get_rank_label(1),
+            self.contents[1][0],
+            self.contents[1][1],
+            self.contents[1][2],
+            self.contents[1][3],
+            self.contents[1][4],
+            self.contents[1][5],
+            self.contents[1][6],
+            self.contents[1][7],
+            Square::get_rank_label(1),
+
+            Square::get_rank_label(2),
+            self.contents[2][0],
+            self.contents[2][1],
+            self.contents[2][2],
+            self.contents[2][3],
+            self.contents[2][4],
+            self.contents[2][5],
+            self.contents[2][6],
+            self.contents[2][7],
+            Square::get_rank_label(2),
+
+            Square::get_rank_label(3),
+            self.contents[3][0],
+            self.contents[3][1],
+            self.contents[3][2],
+            self.contents[3][3],
+            self.contents[3][4],
+            self.contents[3][5],
+            self.contents[3][6],
+            self.contents[3][7],
+            Square::get_rank_label(3),
+
+            Square::get_rank_label(4),
+            self.contents[4][0],
+            self.contents[4][1],
+            self.contents[4][2],
+            self.contents[4][3],
+            self.contents[4][4],
+            self.contents[4][5],
+            self.contents[4][6],
+            self.contents[4][7],
+            Square::get_rank_label(4),
+
+            Square::get_rank_label(5),
+            self.contents[5][0],
+            self.contents[5][1],
+            self.contents[5][2],
+            self.contents[5][3],
+            self.contents[5][4],
+            self.contents[5][5],
+            self.contents[5][6],
+            self.contents[5][7],
+            Square::get_rank_label(5),
+
+            Square::get_rank_label(6),
+            self.contents[6][0],
+            self.contents[6][1],
+            self.contents[6][2],
+            self.contents[6][3],
+            self.contents[6][4],
+            self.contents[6][5],
+            self.contents[6][6],
+            self.contents[6][7],
+            Square::get_rank_label(6),
+
+            Square::get_rank_label(7),
+            self.contents[7][0],
+            self.contents[7][1],
+            self.contents[7][2],
+            self.contents[7][3],
+            self.contents[7][4],
+            self.contents[7][5],
+            self.contents[7][6],
+            self.contents[7][7],
+            Square::get_rank_label(7),
+
+            Square::get_file_label(0),
+            Square::get_file_label(1),
+            Square::get_file_label(2),
+            Square::get_file_label(3),
+            Square::get_file_label(4),
+            Square::get_file_label(5),
+            Square::get_file_label(6),
+            Square::get_file_label(7)
         )
     }
 
@@ -178,215 +211,590 @@ impl Display for ChessBoard {
 
 impl ChessBoard {
 
-    pub fn get_rank_label(rank: u8) -> String {
-        if rank < 8 {
-            (8 - rank).to_string()
-        } else {
-            log(
-                "ERROR",
-                format!(
-                    "crate::components::chess_board::ChessBoard::get_rank_label received the invalid rank number {}",
-                    rank
-                )
-            );
-            panic!("Invalid rank number: {}", rank);
+    pub fn construct(
+        contents: [[ChessPiece; 8]; 8],
+        turn: Color,
+        last_move: LastMove,
+        halfmove_clock: u32,
+        fullmove_number: u32
+    ) -> Self {
+        let mut chess_board = Self {
+            contents,
+            turn,
+            last_move,
+            halfmove_clock,
+            fullmove_number,
+            hash: 0,
+        };
+        chess_board.hash = zobrist::compute_hash(&chess_board);
+        return chess_board;
+    }
+
+}
+
+
+impl ChessBoard {
+
+    pub fn get_turn(&self) -> Color {
+        self.turn
+    }
+
+    pub fn get_last_move(&self) -> &LastMove {
+        &self.last_move
+    }
+
+    pub fn get_halfmove_clock(&self) -> u32 {
+        self.halfmove_clock
+    }
+
+    pub fn get_fullmove_number(&self) -> u32 {
+        self.fullmove_number
+    }
+
+    // Equal for boards with the same pieces, side to move, castling rights and en passant capture, whatever the move counters
+    pub fn get_hash(&self) -> u64 {
+        self.hash
+    }
+
+    pub fn borrow_space_contents(&self, square: Square) -> &ChessPiece {
+        &(self.contents[square.rank() as usize][square.file() as usize])
+    }
+
+    // The contents of the space this many ranks and files away, if it is on the board
+    pub fn borrow_offset_space_contents(&self, square: Square, rank_offset: i8, file_offset: i8) -> Option<&ChessPiece> {
+        square.offset(rank_offset, file_offset).map(|square| self.borrow_space_contents(square))
+    }
+
+    fn borrow_mut_space_contents(&mut self, square: Square) -> &mut ChessPiece {
+        &mut (self.contents[square.rank() as usize][square.file() as usize])
+    }
+
+    fn clone_space_contents(&self, square: Square) -> ChessPiece {
+        self.borrow_space_contents(square).clone()
+    }
+
+    fn set_space_contents(&mut self, square: Square, contents:ChessPiece) {
+        *self.borrow_mut_space_contents(square) = contents;
+    }
+
+    fn is_valid_move(&self, start: Square, target: Square, piece: &ChessPiece) -> bool {
+        match piece.get_type() {
+            None                     => false,
+            Some(PieceType::Pawn)   =>   pawn::is_valid_move(start, target, self),
+            Some(PieceType::Rook)   =>   rook::is_valid_move(start, target, self),
+            Some(PieceType::Knight) => knight::is_valid_move(start, target, self),
+            Some(PieceType::Bishop) => bishop::is_valid_move(start, target, self),
+            Some(PieceType::Queen)  =>  queen::is_valid_move(start, target, self),
+            Some(PieceType::King)   =>   king::is_valid_move(start, target, self),
         }
     }
-    
-    pub fn get_rank(rank_label: String) -> u8 {
-        let parsed_label = rank_label.parse::<u8>();
-        match parsed_label {
-            Ok(_) => {
-                let rank = 8 - parsed_label.unwrap();
-                if rank < 8 {
-                    rank
-                } else {
-                    log(
-                        "ERROR",
-                        format!(
-                            "crate::components::chess_board::ChessBoard::get_rank received the invalid rank label \"{}\"",
-                            rank_label
-                        )
-                    );
-                    panic!("Invalid rank label: {}", rank_label);
+
+    pub fn move_piece(&mut self, start: Square, target: Square, promotion: Option<PieceType>) -> Result<UndoRecord, ChessError> {
+        let move_label = format!("{} -> {}", start, target);
+        let turn = self.turn;
+        if !self.borrow_space_contents(start).is_color(turn) {
+            return Err(ChessError::IllegalMove(format!("{}: there is no {} piece to move there.", move_label, turn)));
+        }
+        if !self.is_valid_move(start, target, self.borrow_space_contents(start)) {
+            log("INFO", format!("Skipped move {} due to it being invalid.", move_label));
+            return Err(ChessError::IllegalMove(format!("{}: the piece can't move that way.", move_label)));
+        }
+
+        // Ensure a promotion piece is given exactly when a pawn reaches the last rank
+        if self.is_promotion(start, target) {
+            match promotion {
+                None => {
+                    log("INFO", "Skipped move due to it being a promotion without a chosen piece.");
+                    return Err(ChessError::IllegalMove(format!("{}: a promotion needs a piece to promote to.", move_label)));
+                },
+                Some(piece_type) => {
+                    if !PROMOTION_TYPES.contains(&piece_type) {
+                        log(
+                            "INFO",
+                            format!(
+                                "Skipped move due to it promoting to a {}.",
+                                piece_type
+                            )
+                        );
+                        return Err(ChessError::IllegalMove(format!("{}: a pawn can't promote to a {}.", move_label, piece_type)));
+                    }
                 }
-            },
-            Err(_) => {
-                log(
-                    "ERROR",
-                    format!(
-                        "crate::components::chess_board::ChessBoard::get_rank received the invalid rank label \"{}\"",
-                        rank_label
-                    )
-                );
-                panic!("Invalid rank label: {}", rank_label);
             }
+        } else if promotion.is_some() {
+            log("INFO", "Skipped move due to it naming a promotion piece without being a promotion.");
+            return Err(ChessError::IllegalMove(format!("{}: the move is not a promotion.", move_label)));
+        }
+
+        // Ensure the move doesn't leave the mover's own king in check
+        let chess_move = ChessMove::construct(start, target, self.get_move_kind(start, target, promotion));
+        if !self.leaves_king_safe(&chess_move) {
+            log("INFO", format!("Skipped move {} due to it leaving the king in check.", move_label));
+            return Err(ChessError::IllegalMove(format!("{}: the move would leave the king in check.", move_label)));
         }
+
+        log("INFO", format!("Performing move {}", to_san(self, &chess_move)));
+        let undo_record = self.make_move(&chess_move);
+
+        if self.is_in_check(turn.opposite()) {
+            log("INFO", "The opposing king is now in check.");
+        }
+
+        return Ok(undo_record);
     }
 
-    pub fn get_file_label(file: u8) -> String {
-        String::from(
-            match file {
-                0 => 'a',
-                1 => 'b',
-                2 => 'c',
-                3 => 'd',
-                4 => 'e',
-                5 => 'f',
-                6 => 'g',
-                7 => 'h',
-                _ => {
-                    log(
-                        "ERROR",
-                        format!(
-                            "crate::components::chess_board::ChessBoard::get_file_label received the invalid file number {}",
-                            file
-                        )
-                    );
-                    panic!("Invalid file number: {}", file);
-                }
-            }
-        )
+    // Whether moving the piece at start to the target's rank would be a pawn promotion
+    pub fn is_promotion(&self, start: Square, target: Square) -> bool {
+        let piece = self.borrow_space_contents(start);
+        piece.is_pawn() && target.rank() == if piece.is_black() { RANK_1 } else { RANK_8 }
+    }
+
+    // Whether the side to move could make this move (ignoring which piece a promotion would choose)
+    pub fn is_legal_move(&self, start: Square, target: Square) -> bool {
+        let piece = self.borrow_space_contents(start);
+        piece.is_color(self.turn)
+        && with_logs_muted(|| self.is_valid_move(start, target, piece))
+        && self.leaves_king_safe(&ChessMove::construct(start, target, self.get_move_kind(start, target, None)))
     }
 
-    pub fn get_file(file_label: String) -> u8 {
-        if      file_label == "a" { 0 }
-        else if file_label == "b" { 1 }
-        else if file_label == "c" { 2 }
-        else if file_label == "d" { 3 }
-        else if file_label == "e" { 4 }
-        else if file_label == "f" { 5 }
-        else if file_label == "g" { 6 }
-        else if file_label == "h" { 7 }
-        else {
-            log(
-                "ERROR",
-                format!(
-                    "crate::components::chess_board::ChessBoard::get_file_label received the invalid file label {}",
-                    file_label
-                )
-            );
-            panic!("Invalid file label {}", file_label);
+    // Works out what kind of move this is, assuming it has already been validated
+    fn get_move_kind(&self, start: Square, target: Square, promotion: Option<PieceType>) -> MoveKind {
+        let piece = self.borrow_space_contents(start);
+        if piece.is_king() && start.file().abs_diff(target.file()) == 2 {
+            if target.file() > start.file() { MoveKind::KingsideCastle } else { MoveKind::QueensideCastle }
+        } else if piece.is_pawn() && start.file() != target.file() && self.borrow_space_contents(target).is_empty() {
+            MoveKind::EnPassant
+        } else if let Some(piece_type) = promotion {
+            MoveKind::Promotion(piece_type)
+        } else {
+            MoveKind::Normal
         }
     }
 
-}
+    // Plays a move that has already been validated, returning what is needed to unmake it
+    pub fn make_move(&mut self, chess_move: &ChessMove) -> UndoRecord {
+        let undo_record = UndoRecord {
+            chess_move: chess_move.clone(),
+            moved_piece: self.clone_space_contents(chess_move.start),
+            captured_piece: self.clone_space_contents(chess_move.get_capture_square()),
+            last_move: self.last_move,
+            halfmove_clock: self.halfmove_clock,
+            hash: self.hash,
+        };
+
+        // Take the changed spaces and the rest of the state out of the hash, and put them back in once the move is played
+        let changed_spaces = Self::get_changed_spaces(chess_move);
+        let mut hash = self.hash ^ self.get_spaces_key(&changed_spaces) ^ zobrist::get_state_key(self);
+
+        // Pawn moves and captures reset the halfmove clock
+        if undo_record.moved_piece.is_pawn() || !undo_record.captured_piece.is_empty() {
+            self.halfmove_clock = 0;
+        } else {
+            self.halfmove_clock += 1;
+        }
+        if self.turn == Color::Black {
+            self.fullmove_number += 1;
+        }
+        self.perform_move(chess_move);
+        self.last_move = Some((chess_move.start, chess_move.target));
+        self.turn = self.turn.opposite();
+        hash ^= self.get_spaces_key(&changed_spaces) ^ zobrist::get_state_key(self);
+        self.hash = hash;
+        return undo_record;
+    }
 
+    // Takes back the most recent move, restoring the board exactly as it was
+    pub fn unmake_move(&mut self, undo_record: &UndoRecord) {
+        let chess_move = &undo_record.chess_move;
+        self.turn = self.turn.opposite();
+        if self.turn == Color::Black {
+            self.fullmove_number -= 1;
+        }
+        self.last_move = undo_record.last_move;
+        self.halfmove_clock = undo_record.halfmove_clock;
+        self.hash = undo_record.hash;
+
+        // Put the moving piece back and restore whatever it captured
+        self.set_space_contents(chess_move.start, undo_record.moved_piece.clone());
+        self.set_space_contents(chess_move.target, ChessPiece::default());
+        self.set_space_contents(chess_move.get_capture_square(), undo_record.captured_piece.clone());
+
+        // A castling rook goes back to its corner; it can't have moved before castling
+        if let Some((corner, rook_target)) = chess_move.get_castling_rook_move() {
+            let color = if undo_record.moved_piece.is_black() { Color::Black } else { Color::White };
+            self.set_space_contents(rook_target, ChessPiece::default());
+            self.set_space_contents(corner, ChessPiece::construct(color, PieceType::Rook));
+        }
+    }
 
-impl ChessBoard {
+    // The spaces a move puts a piece on or takes one off of
+    fn get_changed_spaces(chess_move: &ChessMove) -> Vec<Square> {
+        let mut spaces = vec![chess_move.start, chess_move.target];
+        if chess_move.kind == MoveKind::EnPassant {
+            spaces.push(chess_move.get_capture_square());
+        }
+        if let Some((corner, rook_target)) = chess_move.get_castling_rook_move() {
+            spaces.extend([corner, rook_target]);
+        }
+        return spaces;
+    }
 
-    pub fn borrow_space_contents(&self, rank: u8, file: u8) -> &ChessPiece {
-        &(self.contents[rank as usize][file as usize])
+    // The combined keys of whatever is on the given spaces
+    fn get_spaces_key(&self, spaces: &[Square]) -> u64 {
+        spaces.iter().fold(0, |key, square| {
+            key ^ zobrist::get_piece_key(self.borrow_space_contents(*square), *square)
+        })
     }
 
-    fn borrow_mut_space_contents(&mut self, rank: u8, file: u8) -> &mut ChessPiece {
-        &mut (self.contents[rank as usize][file as usize])
+    fn move_castling_rook(&mut self, chess_move: &ChessMove) {
+        if let Some((corner, rook_target)) = chess_move.get_castling_rook_move() {
+            self.set_space_contents(rook_target, self.clone_space_contents(corner));
+            self.set_space_contents(corner, ChessPiece::default());
+            self.borrow_mut_space_contents(rook_target).mark_moved();
+        }
     }
 
-    fn clone_space_contents(&self, rank: u8, file: u8) -> ChessPiece {
-        self.contents[rank as usize][file as usize].clone()
+    // Updates the spaces for a move that has already been validated
+    fn perform_move(&mut self, chess_move: &ChessMove) {
+        let start = chess_move.start;
+        let target = chess_move.target;
+
+        match &chess_move.kind {
+            MoveKind::Normal => (),
+
+            // Remove the pawn captured en passant, which sits beside the start position
+            MoveKind::EnPassant => {
+                log("INFO", "Detected en passant.");
+                self.set_space_contents(chess_move.get_capture_square(), ChessPiece::default());
+            },
+
+            // Move the rook to the far side of the king
+            MoveKind::KingsideCastle => {
+                log("INFO", "Detected kingside castle.");
+                self.move_castling_rook(chess_move);
+            },
+            MoveKind::QueensideCastle => {
+                log("INFO", "Detected queenside castle.");
+                self.move_castling_rook(chess_move);
+            },
+
+            // The pawn is replaced after it moves, below
+            MoveKind::Promotion(piece_type) => {
+                log("INFO", format!("Detected promotion to {}.", piece_type));
+            },
+        }
+
+        // Move piece (typical updates)
+        self.set_space_contents(target, self.clone_space_contents(start));
+        self.set_space_contents(start, ChessPiece::default());
+        self.borrow_mut_space_contents(target).mark_moved();
+
+        // Replace a promoted pawn with the chosen piece
+        if let MoveKind::Promotion(piece_type) = chess_move.kind {
+            let color = if self.borrow_space_contents(target).is_black() { Color::Black } else { Color::White };
+            let mut promoted_piece = ChessPiece::construct(color, piece_type);
+            promoted_piece.mark_moved();
+            self.set_space_contents(target, promoted_piece);
+        }
     }
 
-    // fn space_is_empty(&self, rank: u8, file: u8) -> bool {
-    //     self.contents[rank as usize][file as usize].is_empty()
-    // }
+}
 
-    fn set_space_contents(&mut self, rank: u8, file: u8, contents:ChessPiece) {
-        self.contents[rank as usize][file as usize] = contents;
+
+impl ChessBoard {
+
+    pub fn find_king(&self, color: Color) -> Option<Square> {
+        Square::all().find(|square| {
+            let piece = self.borrow_space_contents(*square);
+            piece.is_king() && piece.is_color(color)
+        })
     }
 
-    fn is_valid_move(&self, start_rank: u8, start_file: u8, target_rank: u8, target_file: u8, piece: &ChessPiece, last_move: &Option<((u8, u8), (u8, u8))>) -> bool {
-        let _type = piece.get_type();
-        if      _type == "none"   { false }
-        else if _type == "pawn"   {   pawn::is_valid_move(start_rank, start_file, target_rank, target_file, &self, last_move) }
-        else if _type == "rook"   {   rook::is_valid_move(start_rank, start_file, target_rank, target_file, &self) }
-        else if _type == "knight" { knight::is_valid_move(start_rank, start_file, target_rank, target_file, &self) }
-        else if _type == "bishop" { bishop::is_valid_move(start_rank, start_file, target_rank, target_file, &self) }
-        else if _type == "queen"  {  queen::is_valid_move(start_rank, start_file, target_rank, target_file, &self) }
-        else if _type == "king"   {   king::is_valid_move(start_rank, start_file, target_rank, target_file, &self) }
-        else {
-            log(
-                "ERROR",
-                format!(
-                    "crate::components::chess_board::ChessBoard::is_valid_move received a ChessPiece with the invalid type \"{}\".",
-                    _type
-                )
-            );
-            panic!("Invalid piece type: {}", _type);
+    pub fn is_in_check(&self, color: Color) -> bool {
+        match self.find_king(color) {
+            Some(square) => self.is_space_attacked(square, color.opposite()),
+            None => false,
         }
     }
 
-    pub fn move_piece(&mut self, is_black_turn: bool, start_rank: u8, start_file: u8, target_rank: u8, target_file: u8, last_move: &mut Option<((u8, u8), (u8, u8))>) -> bool {
-        if self.borrow_space_contents(start_rank, start_file).is_black() != is_black_turn {
-            return false;
+    // Whether any piece of the given color could capture on the given space
+    pub fn is_space_attacked(&self, square: Square, by_color: Color) -> bool {
+        // Pawns (black pawns attack towards higher rank indices, white towards lower)
+        for file_offset in [-1, 1] {
+            if let Some(piece) = self.borrow_offset_space_contents(square, -by_color.get_forward(), file_offset)
+            && piece.is_pawn() && piece.is_color(by_color) {
+                return true;
+            }
+        }
+
+        // Knights
+        for offset in knight::POSSIBLE_MOVES {
+            if let Some(piece) = self.borrow_offset_space_contents(square, offset[0], offset[1])
+            && piece.is_knight() && piece.is_color(by_color) {
+                return true;
+            }
         }
-        if self.is_valid_move(
-            start_rank, start_file,
-            target_rank, target_file,
-            self.borrow_space_contents(start_rank, start_file),
-            last_move,
-        ) {
-            log(
-                "INFO",
-                format!(
-                    "Performing move {}{} -> {}{}",
-                    Self::get_file_label(start_file),
-                    Self::get_rank_label(start_rank),
-                    Self::get_file_label(target_file),
-                    Self::get_rank_label(target_rank)
-                )
-            );
-
-            // Check for en passant and remove the attacked pawn
-            if self.borrow_space_contents(start_rank, start_file).is_pawn()
-            && self.borrow_space_contents(target_rank, target_file).is_empty()
-            && start_file != target_file {
-                log("INFO", "Detected en passant.");
-                if self.borrow_space_contents(start_rank, start_file).is_white() {
-                    self.set_space_contents(target_rank + 1, target_file, ChessPiece::default());
-                } else {
-                    self.set_space_contents(target_rank - 1, target_file, ChessPiece::default());
+
+        // Kings
+        for rank_offset in -1..=1 {
+            for file_offset in -1..=1 {
+                if let Some(piece) = self.borrow_offset_space_contents(square, rank_offset, file_offset)
+                && piece.is_king() && piece.is_color(by_color) {
+                    return true;
+                }
+            }
+        }
+
+        // Rooks and queens along files and ranks, bishops and queens along diagonals
+        for (rank_increment, file_increment) in [(1, 0), (-1, 0), (0, 1), (0, -1), (1, 1), (1, -1), (-1, 1), (-1, -1)] {
+            let is_diagonal = rank_increment != 0 && file_increment != 0;
+            let mut current = square.offset(rank_increment, file_increment);
+            while let Some(current_square) = current {
+                let piece = self.borrow_space_contents(current_square);
+                if !piece.is_empty() {
+                    if piece.is_color(by_color)
+                    && (piece.is_queen() || if is_diagonal { piece.is_bishop() } else { piece.is_rook() }) {
+                        return true;
+                    }
+                    break;
                 }
+                current = current_square.offset(rank_increment, file_increment);
             }
+        }
+
+        false
+    }
 
-            // Check for castle and move the associated rook
-            if self.borrow_space_contents(start_rank, start_file).is_king()
-            && (start_file as i8 - target_file as i8) == 2 {
-                log("INFO", "Detected castle.");
-                if target_file < start_file {
-                    log("INFO", "Detected queenside castle.");
-                    let rook_file = ChessBoard::get_file(String::from("a"));
-                    self.set_space_contents(start_rank, target_file + 1, self.clone_space_contents(start_rank, rook_file));
-                    self.set_space_contents(start_rank, rook_file, ChessPiece::default());
+    // Every move the given side can make without leaving its king in check
+    pub fn legal_moves(&self, color: Color) -> Vec<ChessMove> {
+        let mut moves: Vec<ChessMove> = vec![];
+        for start in Square::all() {
+            let piece = self.borrow_space_contents(start);
+            if !piece.is_color(color) {
+                continue;
+            }
+            for target in self.get_valid_targets(start, piece) {
+                let chess_move = ChessMove::construct(start, target, self.get_move_kind(start, target, None));
+                if !self.leaves_king_safe(&chess_move) {
+                    continue;
+                }
+                // a promotion is one move per choice of piece
+                if self.is_promotion(start, target) {
+                    for piece_type in PROMOTION_TYPES {
+                        moves.push(ChessMove::construct(start, target, MoveKind::Promotion(piece_type)));
+                    }
                 } else {
-                    log("INFO", "Detected kingside castle.");
-                    let rook_file = ChessBoard::get_file(String::from("h"));
-                    self.set_space_contents(start_rank, target_file - 1, self.clone_space_contents(start_rank, rook_file));
-                    self.set_space_contents(start_rank, rook_file, ChessPiece::default());
+                    moves.push(chess_move);
                 }
             }
+        }
+        moves
+    }
+
+    // The legal moves of the piece on this space, if it belongs to the side to move
+    pub fn legal_moves_from(&self, start: Square) -> Vec<ChessMove> {
+        self.legal_moves(self.turn)
+            .into_iter()
+            .filter(|chess_move| chess_move.start == start)
+            .collect()
+    }
+
+    // Every space the piece could move to, ignoring whether its own king would be left in check
+    fn get_valid_targets(&self, start: Square, piece: &ChessPiece) -> Vec<Square> {
+        match piece.get_type() {
+            None                     => vec![],
+            Some(PieceType::Pawn)   =>   pawn::get_valid_moves(start, self),
+            Some(PieceType::Rook)   =>   rook::get_valid_moves(start, self),
+            Some(PieceType::Knight) => knight::get_valid_moves(start, self),
+            Some(PieceType::Bishop) => bishop::get_valid_moves(start, self),
+            Some(PieceType::Queen)  =>  queen::get_valid_moves(start, self),
+            Some(PieceType::King)   =>   king::get_valid_moves(start, self),
+        }
+    }
+
+    // Evaluates the game from the perspective of the side to move
+    pub fn get_outcome(&self) -> GameOutcome {
+        let turn = self.turn;
+        if !self.legal_moves(turn).is_empty() {
+            GameOutcome::InProgress
+        } else if self.is_in_check(turn) {
+            GameOutcome::Checkmate { winner: turn.opposite() }
+        } else {
+            GameOutcome::Stalemate
+        }
+    }
 
-            // Move piece (typical updates)
-            self.set_space_contents(target_rank, target_file, self.clone_space_contents(start_rank, start_file));
-            self.set_space_contents(start_rank, start_file, ChessPiece::default());
-            self.borrow_mut_space_contents(target_rank, target_file).mark_moved();
-            *last_move = Some(((start_file, start_rank), (target_file, target_rank)));
+    // Tries the move on a copy of the board and checks the mover's king afterwards
+    fn leaves_king_safe(&self, chess_move: &ChessMove) -> bool {
+        let color = if self.borrow_space_contents(chess_move.start).is_black() { Color::Black } else { Color::White };
+        let mut board_after = self.clone();
+        with_logs_muted(|| board_after.perform_move(chess_move));
+        !board_after.is_in_check(color)
+    }
+
+}
+
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    // a board in the starting position with the pieces between the kings and rooks removed
+    fn make_castling_board() -> ChessBoard {
+        let mut chess_board = ChessBoard::default();
+        for square in [Square::B8, Square::C8, Square::D8, Square::F8, Square::G8, Square::B1, Square::C1, Square::D1, Square::F1, Square::G1] {
+            chess_board.set_space_contents(square, ChessPiece::default());
+        }
+        chess_board
+    }
+
+    fn assert_castled(chess_board: &ChessBoard, king: Square, rook: Square, old_king: Square, old_rook: Square) {
+        assert!(chess_board.borrow_space_contents(king).is_king());
+        assert!(chess_board.borrow_space_contents(rook).is_rook());
+        assert!(chess_board.borrow_space_contents(rook).has_moved());
+        assert!(chess_board.borrow_space_contents(old_king).is_empty());
+        assert!(chess_board.borrow_space_contents(old_rook).is_empty());
+    }
+
+    #[test]
+    fn white_kingside_castle_moves_the_rook() {
+        let mut chess_board = make_castling_board();
+        assert!(chess_board.move_piece(Square::E1, Square::G1, None).is_ok());
+        assert_castled(&chess_board, Square::G1, Square::F1, Square::E1, Square::H1);
+    }
+
+    #[test]
+    fn white_queenside_castle_moves_the_rook() {
+        let mut chess_board = make_castling_board();
+        assert!(chess_board.move_piece(Square::E1, Square::C1, None).is_ok());
+        assert_castled(&chess_board, Square::C1, Square::D1, Square::E1, Square::A1);
+    }
+
+    #[test]
+    fn black_kingside_castle_moves_the_rook() {
+        let mut chess_board = make_castling_board();
+        chess_board.turn = Color::Black;
+        assert!(chess_board.move_piece(Square::E8, Square::G8, None).is_ok());
+        assert_castled(&chess_board, Square::G8, Square::F8, Square::E8, Square::H8);
+    }
+
+    #[test]
+    fn black_queenside_castle_moves_the_rook() {
+        let mut chess_board = make_castling_board();
+        chess_board.turn = Color::Black;
+        assert!(chess_board.move_piece(Square::E8, Square::C8, None).is_ok());
+        assert_castled(&chess_board, Square::C8, Square::D8, Square::E8, Square::A8);
+    }
+
+    #[test]
+    fn outcome_is_checkmate_stalemate_or_in_progress() {
+        // fool's mate: 1. f3 e5 2. g4 Qh4#
+        let mut chess_board = ChessBoard::default();
+        for (start, target) in [(Square::F2, Square::F3), (Square::E7, Square::E5), (Square::G2, Square::G4)] {
+            assert!(chess_board.move_piece(start, target, None).is_ok());
+            assert_eq!(chess_board.get_outcome(), GameOutcome::InProgress);
+        }
+        assert!(chess_board.move_piece(Square::D8, Square::H4, None).is_ok());
+        assert_eq!(chess_board.get_outcome(), GameOutcome::Checkmate { winner: Color::Black });
+
+        // the black king on a8 has no moves and isn't in check
+        let chess_board = ChessBoard::from_fen("k7/2Q5/1K6/8/8/8/8/8 b - - 0 1").unwrap();
+        assert_eq!(chess_board.get_outcome(), GameOutcome::Stalemate);
+        // with a pawn that can still move, it is only in progress
+        let chess_board = ChessBoard::from_fen("k7/2Q5/1K6/8/8/8/p7/8 b - - 0 1").unwrap();
+        assert_eq!(chess_board.get_outcome(), GameOutcome::InProgress);
+    }
+
+    #[test]
+    fn initial_position_has_twenty_legal_moves_per_side() {
+        let chess_board = ChessBoard::default();
+        assert_eq!(chess_board.legal_moves(Color::White).len(), 20);
+        assert_eq!(chess_board.legal_moves(Color::Black).len(), 20);
+    }
+
+    #[test]
+    fn legal_moves_from_only_covers_the_side_to_move() {
+        let chess_board = ChessBoard::default();
+        let knight_moves = chess_board.legal_moves_from(Square::B1);
+        assert_eq!(knight_moves.len(), 2);
+        assert!(knight_moves.contains(&ChessMove::construct(Square::B1, Square::A3, MoveKind::Normal)));
+        assert!(knight_moves.contains(&ChessMove::construct(Square::B1, Square::C3, MoveKind::Normal)));
+        assert!(chess_board.legal_moves_from(Square::A1).is_empty());
+        assert!(chess_board.legal_moves_from(Square::B8).is_empty());
+    }
+
+    #[test]
+    fn legal_moves_include_castling_en_passant_and_promotions() {
+        let mut chess_board = make_castling_board();
+        // a white pawn on b7, which can promote on the empty b8
+        chess_board.set_space_contents(Square::B7, ChessPiece::construct(Color::White, PieceType::Pawn));
+        // a white pawn on e5 next to a black pawn that has just moved d7 -> d5
+        chess_board.set_space_contents(Square::E5, ChessPiece::construct(Color::White, PieceType::Pawn));
+        chess_board.set_space_contents(Square::D7, ChessPiece::default());
+        chess_board.set_space_contents(Square::D5, ChessPiece::construct(Color::Black, PieceType::Pawn));
+        chess_board.last_move = Some((Square::D7, Square::D5));
+        let moves = chess_board.legal_moves(Color::White);
+        assert!(moves.contains(&ChessMove::construct(Square::E1, Square::G1, MoveKind::KingsideCastle)));
+        assert!(moves.contains(&ChessMove::construct(Square::E1, Square::C1, MoveKind::QueensideCastle)));
+        assert!(moves.contains(&ChessMove::construct(Square::E5, Square::D6, MoveKind::EnPassant)));
+        for piece_type in PROMOTION_TYPES {
+            assert!(moves.contains(&ChessMove::construct(Square::B7, Square::B8, MoveKind::Promotion(piece_type))));
+        }
+    }
 
-            // TODO: check for promotion here (once implemented)
+    #[test]
+    fn castling_through_an_attacked_space_is_rejected() {
+        let mut chess_board = make_castling_board();
+        // a black rook on f3 attacks f1
+        chess_board.set_space_contents(Square::F2, ChessPiece::default());
+        chess_board.set_space_contents(Square::F3, ChessPiece::construct(Color::Black, PieceType::Rook));
+        assert!(chess_board.move_piece(Square::E1, Square::G1, None).is_err());
+        assert!(chess_board.borrow_space_contents(Square::E1).is_king());
+        assert!(chess_board.move_piece(Square::E1, Square::C1, None).is_ok());
+    }
+
+    #[test]
+    fn rejected_moves_say_why() {
+        let mut chess_board = ChessBoard::default();
+        assert!(matches!(chess_board.move_piece(Square::E7, Square::E5, None), Err(ChessError::IllegalMove(_))));
+        assert!(matches!(chess_board.move_piece(Square::E2, Square::E5, None), Err(ChessError::IllegalMove(_))));
+        assert!(matches!(chess_board.move_piece(Square::E2, Square::E3, Some(PieceType::Queen)), Err(ChessError::IllegalMove(_))));
+        assert!(chess_board.borrow_space_contents(Square::E2).is_pawn());
+    }
 
-            return true;
+    #[test]
+    fn moves_that_leave_the_king_in_check_are_rejected() {
+        // the bishop on e2 is pinned by the rook on e7, and the rook on d8 covers the d file
+        let fen = "3rk3/4r3/8/8/8/8/4B3/4K3 w - - 0 1";
+        let mut chess_board = ChessBoard::from_fen(fen).unwrap();
+        let hash = chess_board.get_hash();
+        for (start, target) in [(Square::E2, Square::D3), (Square::E1, Square::D1)] {
+            assert!(matches!(chess_board.move_piece(start, target, None), Err(ChessError::IllegalMove(_))));
+            assert_eq!(chess_board.to_fen(), fen);
+            assert_eq!(chess_board.get_hash(), hash);
+        }
+        assert!(chess_board.move_piece(Square::E1, Square::F1, None).is_ok());
+    }
+
+    #[test]
+    fn promotion_needs_a_piece_a_pawn_can_become() {
+        let fen = "4k3/1P6/8/8/8/8/8/4K3 w - - 0 1";
+        let mut chess_board = ChessBoard::from_fen(fen).unwrap();
+        let hash = chess_board.get_hash();
+        for promotion in [None, Some(PieceType::King), Some(PieceType::Pawn)] {
+            assert!(matches!(chess_board.move_piece(Square::B7, Square::B8, promotion), Err(ChessError::IllegalMove(_))));
+            assert_eq!(chess_board.to_fen(), fen);
+            assert_eq!(chess_board.get_hash(), hash);
+        }
+        // including an underpromotion
+        for piece_type in [PieceType::Queen, PieceType::Knight] {
+            let mut chess_board = ChessBoard::from_fen(fen).unwrap();
+            assert!(chess_board.move_piece(Square::B7, Square::B8, Some(piece_type)).is_ok());
+            let promoted = chess_board.borrow_space_contents(Square::B8);
+            assert_eq!(promoted.get_type(), Some(piece_type));
+            assert!(promoted.is_white());
+            assert!(chess_board.borrow_space_contents(Square::B7).is_empty());
         }
-        log(
-            "INFO",
-            format!(
-                "Skipped move {}{} -> {}{} due to it being invalid.",
-                Self::get_file_label(start_file),
-                Self::get_rank_label(start_rank),
-                Self::get_file_label(target_file),
-                Self::get_rank_label(target_rank)
-            )
-        );
-        return false;
     }
 
 }
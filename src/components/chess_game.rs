@@ -0,0 +1,291 @@
+// crate::components::chess_game
+
+use crate::components::chess_board::ChessBoard;
+use crate::components::chess_move::ChessMove;
+use crate::components::chess_move::UndoRecord;
+use crate::components::game_outcome::GameOutcome;
+use crate::components::piece_type::PieceType;
+use crate::components::square::Square;
+use crate::error::ChessError;
+use crate::notation::pgn::PgnGame;
+use crate::notation::pgn::write_pgn;
+use crate::utils::logs::log;
+use crate::utils::logs::with_logs_muted;
+
+// the halfmove clocks, in plies without a capture or pawn move, at which a draw can be claimed and at which it is automatic
+const FIFTY_MOVE_RULE_PLIES: u32 = 100;
+const SEVENTY_FIVE_MOVE_RULE_PLIES: u32 = 150;
+
+
+// A game in progress: the board, the moves that led to it, and any undone moves that can be redone
+#[derive(Clone)]
+pub struct ChessGame {
+    chess_board: ChessBoard,
+    start_board: ChessBoard,
+    move_history: Vec<UndoRecord>,
+    // undone moves, the next one to redo last
+    future_moves: Vec<ChessMove>,
+    outcome: GameOutcome,
+    // a draw the player claimed, with how many moves had been played then
+    claimed_draw: Option<(GameOutcome, usize)>,
+}
+
+
+impl Default for ChessGame {
+
+    fn default() -> Self {
+        Self::construct(ChessBoard::default())
+    }
+
+}
+
+
+impl ChessGame {
+
+    pub fn construct(chess_board: ChessBoard) -> Self {
+        let mut chess_game = Self {
+            start_board: chess_board.clone(),
+            chess_board,
+            move_history: vec![],
+            future_moves: vec![],
+            outcome: GameOutcome::InProgress,
+            claimed_draw: None,
+        };
+        chess_game.update_outcome();
+        return chess_game;
+    }
+
+    // a game read from PGN, set up at its first position with all of its moves ready to redo
+    pub fn from_pgn_game(pgn_game: &PgnGame) -> Self {
+        let mut chess_game = Self::construct(pgn_game.start_board.clone());
+        chess_game.future_moves = pgn_game.moves.iter().rev().cloned().collect();
+        return chess_game;
+    }
+
+}
+
+
+impl ChessGame {
+
+    pub fn borrow_board(&self) -> &ChessBoard {
+        &self.chess_board
+    }
+
+    pub fn borrow_start_board(&self) -> &ChessBoard {
+        &self.start_board
+    }
+
+    pub fn get_outcome(&self) -> GameOutcome {
+        self.outcome
+    }
+
+    // the moves played so far, from the start board
+    pub fn get_moves(&self) -> Vec<ChessMove> {
+        self.move_history.iter()
+            .map(|undo_record| undo_record.chess_move.clone())
+            .collect()
+    }
+
+    // play a move from the current position; any undone moves are dropped, since the game now branches off from them
+    pub fn play_move(&mut self, start: Square, target: Square, promotion: Option<PieceType>) -> Result<(), ChessError> {
+        if self.outcome.is_over() {
+            return Err(ChessError::IllegalMove(format!("The game is over: {}.", self.outcome)));
+        }
+        let undo_record = self.chess_board.move_piece(start, target, promotion)?;
+        self.move_history.push(undo_record);
+        self.future_moves.clear();
+        log("INFO", format!("The position is now {}", self.chess_board.to_fen()));
+        self.update_outcome();
+        if self.outcome.is_over() {
+            log("INFO", format!("The game is over: {}.", self.outcome));
+        }
+        return Ok(());
+    }
+
+    // take back the last move, keeping it to redo later
+    pub fn undo_move(&mut self) -> bool {
+        let undo_record = match self.move_history.pop() {
+            Some(undo_record) => undo_record,
+            None => return false,
+        };
+        log("INFO", "Undoing the last move.");
+        self.chess_board.unmake_move(&undo_record);
+        self.future_moves.push(undo_record.chess_move);
+        self.update_outcome();
+        return true;
+    }
+
+    // replay the last move that was undone
+    pub fn redo_move(&mut self) -> bool {
+        let chess_move = match self.future_moves.pop() {
+            Some(chess_move) => chess_move,
+            None => return false,
+        };
+        log("INFO", "Redoing the last undone move.");
+        let undo_record = with_logs_muted(|| self.chess_board.make_move(&chess_move));
+        self.move_history.push(undo_record);
+        self.update_outcome();
+        return true;
+    }
+
+    // how many times the current position has come up, counting this time
+    pub fn count_repetitions(&self) -> usize {
+        // the undo records keep the hash of the position before each move; a capture or pawn move can't be undone over the board,
+        // so only the positions since the last one can repeat
+        let hash = self.chess_board.get_hash();
+        let earlier_count = self.move_history.iter()
+            .rev()
+            .take(self.chess_board.get_halfmove_clock() as usize)
+            .filter(|undo_record| undo_record.hash == hash)
+            .count();
+        return earlier_count + 1;
+    }
+
+    // the draw the player to move could claim, if any
+    pub fn get_claimable_draw(&self) -> Option<GameOutcome> {
+        if self.outcome.is_over() {
+            None
+        } else if self.count_repetitions() >= 3 {
+            Some(GameOutcome::ThreefoldRepetition)
+        } else if self.chess_board.get_halfmove_clock() >= FIFTY_MOVE_RULE_PLIES {
+            Some(GameOutcome::FiftyMoveRule)
+        } else {
+            None
+        }
+    }
+
+    // end the game in a draw for the player to move, if they can claim one
+    pub fn claim_draw(&mut self) -> bool {
+        match self.get_claimable_draw() {
+            Some(outcome) => {
+                self.outcome = outcome;
+                self.claimed_draw = Some((outcome, self.move_history.len()));
+                log("INFO", format!("The game is over: {}.", self.outcome));
+                return true;
+            },
+            None => return false,
+        }
+    }
+
+    pub fn to_pgn(&self) -> String {
+        write_pgn(&self.start_board, &self.get_moves(), self.outcome)
+    }
+
+    // a claimed draw until the move it was claimed after is undone, otherwise checkmate and stalemate,
+    // then the draws that happen without being claimed; a checkmate stands even on the 75th move
+    fn update_outcome(&mut self) {
+        if let Some((outcome, move_count)) = self.claimed_draw {
+            if self.move_history.len() == move_count {
+                self.outcome = outcome;
+                return;
+            }
+            self.claimed_draw = None;
+        }
+        self.outcome = self.chess_board.get_outcome();
+        if self.outcome.is_over() {
+            return;
+        }
+        if self.count_repetitions() >= 5 {
+            self.outcome = GameOutcome::FivefoldRepetition;
+        } else if self.chess_board.get_halfmove_clock() >= SEVENTY_FIVE_MOVE_RULE_PLIES {
+            self.outcome = GameOutcome::SeventyFiveMoveRule;
+        }
+    }
+
+}
+
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use crate::components::color::Color;
+    use crate::notation::pgn::parse_pgn;
+
+    #[test]
+    fn undone_moves_are_redone_until_a_new_move_is_played() {
+        let mut chess_game = ChessGame::default();
+        chess_game.play_move(Square::E2, Square::E4, None).unwrap();
+        chess_game.play_move(Square::E7, Square::E5, None).unwrap();
+        assert!(chess_game.undo_move());
+        assert!(chess_game.undo_move());
+        assert!(!chess_game.undo_move());
+        assert_eq!(chess_game.borrow_board().to_fen(), ChessBoard::default().to_fen());
+        assert!(chess_game.redo_move());
+        assert_eq!(chess_game.get_moves().len(), 1);
+        chess_game.play_move(Square::D7, Square::D5, None).unwrap();
+        assert!(!chess_game.redo_move());
+        assert!(chess_game.play_move(Square::D7, Square::D5, None).is_err());
+    }
+
+    #[test]
+    fn loaded_games_step_through_to_their_outcome() {
+        let games = parse_pgn("1. f3 e5 2. g4 Qh4# 0-1").unwrap();
+        let mut chess_game = ChessGame::from_pgn_game(&games[0]);
+        assert!(!chess_game.get_outcome().is_over());
+        while chess_game.redo_move() {}
+        assert_eq!(chess_game.get_moves().len(), 4);
+        assert!(chess_game.get_outcome().is_over());
+        assert!(chess_game.to_pgn().contains("2. g4 Qh4# 0-1"));
+    }
+
+    #[test]
+    fn repetitions_can_be_claimed_then_end_the_game() {
+        let mut chess_game = ChessGame::default();
+        // the knights go out and back: Nf3 Nf6 Ng1 Ng8
+        let shuffle = [(Square::G1, Square::F3), (Square::G8, Square::F6), (Square::F3, Square::G1), (Square::F6, Square::G8)];
+        for (start, target) in shuffle.iter().cycle().take(8) {
+            assert_eq!(chess_game.get_claimable_draw(), None);
+            chess_game.play_move(*start, *target, None).unwrap();
+        }
+        assert_eq!(chess_game.count_repetitions(), 3);
+        assert_eq!(chess_game.get_claimable_draw(), Some(GameOutcome::ThreefoldRepetition));
+
+        // claimed, and written to PGN as a draw
+        let mut claimed_game = chess_game.clone();
+        assert!(claimed_game.claim_draw());
+        assert_eq!(claimed_game.get_outcome(), GameOutcome::ThreefoldRepetition);
+        assert!(claimed_game.to_pgn().contains("4. Ng1 Ng8 1/2-1/2"));
+        // after which no more moves can be played, until the last one is taken back
+        assert!(claimed_game.play_move(Square::E2, Square::E4, None).is_err());
+        assert_eq!(claimed_game.get_outcome(), GameOutcome::ThreefoldRepetition);
+        assert!(claimed_game.undo_move());
+        assert_eq!(claimed_game.get_outcome(), GameOutcome::InProgress);
+        assert!(claimed_game.redo_move());
+        assert_eq!(claimed_game.get_outcome(), GameOutcome::InProgress);
+        assert!(claimed_game.play_move(Square::E2, Square::E4, None).is_ok());
+
+        // or played on until the fifth time, which ends it
+        for (start, target) in shuffle.iter().cycle().take(8) {
+            assert!(!chess_game.get_outcome().is_over());
+            chess_game.play_move(*start, *target, None).unwrap();
+        }
+        assert_eq!(chess_game.get_outcome(), GameOutcome::FivefoldRepetition);
+        assert!(!chess_game.claim_draw());
+        assert!(chess_game.play_move(Square::G1, Square::F3, None).is_err());
+        assert!(chess_game.undo_move());
+        assert_eq!(chess_game.get_outcome(), GameOutcome::InProgress);
+    }
+
+    #[test]
+    fn long_games_without_captures_or_pawn_moves_are_drawn() {
+        let mut chess_game = ChessGame::construct(ChessBoard::from_fen("4k3/8/8/8/8/8/4P3/R3K3 w - - 99 80").unwrap());
+        assert_eq!(chess_game.get_claimable_draw(), None);
+        chess_game.play_move(Square::A1, Square::A2, None).unwrap();
+        assert_eq!(chess_game.get_claimable_draw(), Some(GameOutcome::FiftyMoveRule));
+        // a pawn move starts the count over
+        chess_game.play_move(Square::E8, Square::D8, None).unwrap();
+        chess_game.play_move(Square::E2, Square::E3, None).unwrap();
+        assert_eq!(chess_game.get_claimable_draw(), None);
+
+        let mut chess_game = ChessGame::construct(ChessBoard::from_fen("4k3/8/8/8/8/8/4P3/R3K3 w - - 150 80").unwrap());
+        assert_eq!(chess_game.get_outcome(), GameOutcome::SeventyFiveMoveRule);
+        assert!(chess_game.play_move(Square::A1, Square::A2, None).is_err());
+        assert_eq!(chess_game.borrow_board().get_halfmove_clock(), 150);
+        // but checkmate on the last move still counts
+        let mut chess_game = ChessGame::construct(ChessBoard::from_fen("4k3/R7/4K3/8/8/8/8/8 w - - 149 80").unwrap());
+        chess_game.play_move(Square::A7, Square::A8, None).unwrap();
+        assert_eq!(chess_game.get_outcome(), GameOutcome::Checkmate { winner: Color::White });
+    }
+
+}
@@ -0,0 +1,73 @@
+// crate::components::chess_move
+
+use crate::components::chess_board::LastMove;
+use crate::components::chess_piece::ChessPiece;
+use crate::components::piece_type::PieceType;
+use crate::components::square::FILE_A;
+use crate::components::square::FILE_H;
+use crate::components::square::Square;
+
+
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub enum MoveKind {
+    Normal,
+    EnPassant,
+    KingsideCastle,
+    QueensideCastle,
+    Promotion(PieceType),
+}
+
+
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct ChessMove {
+    pub start: Square,
+    pub target: Square,
+    pub kind: MoveKind,
+}
+
+
+impl ChessMove {
+
+    pub fn construct(start: Square, target: Square, kind: MoveKind) -> Self {
+        Self {
+            start,
+            target,
+            kind,
+        }
+    }
+
+    // the piece a pawn promotes to, if this is a promotion
+    pub fn get_promotion(&self) -> Option<PieceType> {
+        match self.kind {
+            MoveKind::Promotion(piece_type) => Some(piece_type),
+            _ => None,
+        }
+    }
+
+    // where the rook moves from and to, if this is a castle
+    pub fn get_castling_rook_move(&self) -> Option<(Square, Square)> {
+        match self.kind {
+            MoveKind::KingsideCastle => Some((Square::construct(self.start.rank(), FILE_H)?, self.target.offset(0, -1)?)),
+            MoveKind::QueensideCastle => Some((Square::construct(self.start.rank(), FILE_A)?, self.target.offset(0, 1)?)),
+            _ => None,
+        }
+    }
+
+    // the space any captured piece is taken from; a pawn captured en passant is beside the start rather than on the target
+    pub fn get_capture_square(&self) -> Square {
+        if self.kind == MoveKind::EnPassant { self.start.with_file_of(self.target) } else { self.target }
+    }
+
+}
+
+
+// A played move along with everything the board needs to take it back exactly
+#[derive(Clone)]
+pub struct UndoRecord {
+    pub chess_move: ChessMove,
+    pub moved_piece: ChessPiece,
+    pub captured_piece: ChessPiece,
+    pub last_move: LastMove,
+    pub halfmove_clock: u32,
+    pub hash: u64,
+}
@@ -0,0 +1,46 @@
+// crate::components::color
+
+use std::fmt::Display;
+
+
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum Color {
+    White,
+    Black,
+}
+
+
+impl Display for Color {
+
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.get_label())
+    }
+
+}
+
+
+impl Color {
+
+    pub fn get_label(&self) -> &'static str {
+        match self {
+            Self::White => "white",
+            Self::Black => "black",
+        }
+    }
+
+    pub fn opposite(&self) -> Self {
+        match self {
+            Self::White => Self::Black,
+            Self::Black => Self::White,
+        }
+    }
+
+    // The direction this color's pawns move in, in rank numbers (rank 0 is rank 8)
+    pub fn get_forward(&self) -> i8 {
+        match self {
+            Self::White => -1,
+            Self::Black => 1,
+        }
+    }
+
+}
@@ -0,0 +1,45 @@
+// crate::components::game_outcome
+
+use std::fmt::Display;
+
+use crate::components::color::Color;
+
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum GameOutcome {
+    InProgress,
+    Checkmate { winner: Color },
+    Stalemate,
+    // draws the player to move has claimed
+    ThreefoldRepetition,
+    FiftyMoveRule,
+    // draws that end the game as soon as they happen
+    FivefoldRepetition,
+    SeventyFiveMoveRule,
+}
+
+
+impl Display for GameOutcome {
+
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::InProgress => write!(f, "Game in progress"),
+            Self::Checkmate { winner } => write!(f, "Checkmate: {} wins", winner),
+            Self::Stalemate => write!(f, "Stalemate: draw"),
+            Self::ThreefoldRepetition => write!(f, "Draw by threefold repetition"),
+            Self::FiftyMoveRule => write!(f, "Draw by the fifty-move rule"),
+            Self::FivefoldRepetition => write!(f, "Draw by fivefold repetition"),
+            Self::SeventyFiveMoveRule => write!(f, "Draw by the seventy-five-move rule"),
+        }
+    }
+
+}
+
+
+impl GameOutcome {
+
+    pub fn is_over(&self) -> bool {
+        *self != Self::InProgress
+    }
+
+}
@@ -1,4 +1,12 @@
 // crate::components
 
 pub mod chess_board;
+pub mod chess_game;
+pub mod chess_move;
 pub mod chess_piece;
+pub mod color;
+pub mod game_outcome;
+pub mod perft;
+pub mod piece_type;
+pub mod square;
+pub mod zobrist;
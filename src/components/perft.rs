@@ -0,0 +1,195 @@
+// crate::components::perft
+
+use crate::components::chess_board::ChessBoard;
+use crate::components::chess_move::ChessMove;
+use crate::utils::logs::with_logs_muted;
+
+
+// For checking move generation against known node counts
+impl ChessBoard {
+
+    // Counts the positions reached by every sequence of exactly depth legal moves
+    pub fn perft(&self, depth: u32) -> u64 {
+        if depth == 0 {
+            return 1;
+        }
+        let moves = self.legal_moves(self.get_turn());
+        if depth == 1 {
+            return moves.len() as u64;
+        }
+        let mut nodes: u64 = 0;
+        for chess_move in moves {
+            let mut chess_board = self.clone();
+            with_logs_muted(|| chess_board.make_move(&chess_move));
+            nodes += chess_board.perft(depth - 1);
+        }
+        return nodes;
+    }
+
+    // perft split up by the first move, for narrowing down where move generation goes wrong
+    pub fn divide(&self, depth: u32) -> Vec<(ChessMove, u64)> {
+        let mut results: Vec<(ChessMove, u64)> = vec![];
+        if depth == 0 {
+            return results;
+        }
+        for chess_move in self.legal_moves(self.get_turn()) {
+            let mut chess_board = self.clone();
+            with_logs_muted(|| chess_board.make_move(&chess_move));
+            let nodes = chess_board.perft(depth - 1);
+            results.push((chess_move, nodes));
+        }
+        return results;
+    }
+
+}
+
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use crate::components::color::Color;
+    use crate::components::square::Square;
+
+    // Reference positions from https://www.chessprogramming.org/Perft_Results
+    const KIWIPETE: &str = "r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1";
+    const POSITION_3: &str = "8/2p5/3p4/KP5r/1R3p1k/8/4P1P1/8 w - - 0 1";
+    const POSITION_4: &str = "r3k2r/Pppp1ppp/1b3nbN/nP6/BBP1P3/q4N2/Pp1P2PP/R2Q1RK1 w kq - 0 1";
+    const POSITION_4_MIRRORED: &str = "r2q1rk1/pP1p2pp/Q4n2/bbp1p3/Np6/1B3NBn/pPPP1PPP/R3K2R b KQ - 0 1";
+    const POSITION_5: &str = "rnbq1k1r/pp1Pbppp/2p5/8/2B5/8/PPP1NnPP/RNBQK2R w KQ - 1 8";
+    const POSITION_6: &str = "r4rk1/1pp1qppp/p1np1n2/2b1p1B1/2B1P1b1/P1NP1N2/1PP1QPPP/R4RK1 w - - 0 10";
+
+    fn assert_perft(fen: &str, expected_nodes: &[u64]) {
+        let chess_board = ChessBoard::from_fen(fen).unwrap();
+        for (depth, nodes) in expected_nodes.iter().enumerate() {
+            assert_eq!(chess_board.perft(depth as u32 + 1), *nodes, "{} at depth {}", fen, depth + 1);
+        }
+    }
+
+    // Every (start, target) pair that ChessBoard::is_legal_move accepts should be generated, and vice versa
+    fn assert_validation_matches_generation(chess_board: &ChessBoard) {
+        let moves = chess_board.legal_moves(chess_board.get_turn());
+        for start in Square::all() {
+            for target in Square::all() {
+                let is_generated = moves.iter().any(|chess_move| (chess_move.start, chess_move.target) == (start, target));
+                assert_eq!(
+                    chess_board.is_legal_move(start, target),
+                    is_generated,
+                    "{} -> {} in\n{}",
+                    start,
+                    target,
+                    chess_board
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn perft_initial_position() {
+        let chess_board = ChessBoard::default();
+        assert_eq!(chess_board.perft(0), 1);
+        assert_eq!(chess_board.perft(1), 20);
+        assert_eq!(chess_board.perft(2), 400);
+        assert_eq!(chess_board.perft(3), 8_902);
+        assert_eq!(chess_board.perft(4), 197_281);
+    }
+
+    #[test]
+    #[ignore = "slow in debug builds"]
+    fn perft_initial_position_deep() {
+        assert_eq!(ChessBoard::default().perft(5), 4_865_609);
+    }
+
+    #[test]
+    fn perft_kiwipete() {
+        assert_perft(KIWIPETE, &[48, 2_039, 97_862]);
+    }
+
+    #[test]
+    fn perft_position_3() {
+        assert_perft(POSITION_3, &[14, 191, 2_812, 43_238]);
+    }
+
+    #[test]
+    fn perft_position_4() {
+        assert_perft(POSITION_4, &[6, 264, 9_467]);
+        assert_perft(POSITION_4_MIRRORED, &[6, 264, 9_467]);
+    }
+
+    #[test]
+    fn perft_position_5() {
+        assert_perft(POSITION_5, &[44, 1_486, 62_379]);
+    }
+
+    #[test]
+    fn perft_position_6() {
+        assert_perft(POSITION_6, &[46, 2_079, 89_890]);
+    }
+
+    #[test]
+    #[ignore = "slow in debug builds"]
+    fn perft_reference_positions_deep() {
+        assert_perft(KIWIPETE, &[48, 2_039, 97_862, 4_085_603]);
+        assert_perft(POSITION_3, &[14, 191, 2_812, 43_238, 674_624]);
+        assert_perft(POSITION_4, &[6, 264, 9_467, 422_333]);
+        assert_perft(POSITION_5, &[44, 1_486, 62_379, 2_103_487]);
+        assert_perft(POSITION_6, &[46, 2_079, 89_890, 3_894_594]);
+    }
+
+    #[test]
+    fn divide_sums_to_perft() {
+        let chess_board = ChessBoard::default();
+        let results = chess_board.divide(3);
+        assert_eq!(results.len(), 20);
+        assert_eq!(results.iter().map(|(_, nodes)| nodes).sum::<u64>(), chess_board.perft(3));
+    }
+
+    #[test]
+    fn validation_matches_generation_in_reference_positions() {
+        for fen in [KIWIPETE, POSITION_3, POSITION_4, POSITION_4_MIRRORED, POSITION_5, POSITION_6] {
+            let chess_board = ChessBoard::from_fen(fen).unwrap();
+            assert_validation_matches_generation(&chess_board);
+            for chess_move in chess_board.legal_moves(chess_board.get_turn()) {
+                let mut chess_board = chess_board.clone();
+                chess_board.make_move(&chess_move);
+                assert_validation_matches_generation(&chess_board);
+            }
+        }
+    }
+
+    #[test]
+    fn unmake_move_restores_reference_positions() {
+        for fen in [KIWIPETE, POSITION_3, POSITION_4, POSITION_4_MIRRORED, POSITION_5, POSITION_6] {
+            let mut chess_board = ChessBoard::from_fen(fen).unwrap();
+            for first_move in chess_board.legal_moves(chess_board.get_turn()) {
+                let first_record = chess_board.make_move(&first_move);
+                let fen_after_first_move = chess_board.to_fen();
+                for second_move in chess_board.legal_moves(chess_board.get_turn()) {
+                    let second_record = chess_board.make_move(&second_move);
+                    chess_board.unmake_move(&second_record);
+                    assert_eq!(chess_board.to_fen(), fen_after_first_move);
+                }
+                chess_board.unmake_move(&first_record);
+                assert_eq!(chess_board.to_fen(), fen);
+            }
+            assert_eq!(chess_board.perft(2), ChessBoard::from_fen(fen).unwrap().perft(2));
+        }
+    }
+
+    #[test]
+    fn validation_matches_generation() {
+        let chess_board = ChessBoard::default();
+        assert_validation_matches_generation(&chess_board);
+        for first_move in chess_board.legal_moves(Color::White) {
+            let mut chess_board = chess_board.clone();
+            chess_board.make_move(&first_move);
+            assert_validation_matches_generation(&chess_board);
+            for second_move in chess_board.legal_moves(Color::Black) {
+                let mut chess_board = chess_board.clone();
+                chess_board.make_move(&second_move);
+                assert_validation_matches_generation(&chess_board);
+            }
+        }
+    }
+
+}
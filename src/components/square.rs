@@ -0,0 +1,220 @@
+// crate::components::square
+
+use std::fmt::Display;
+
+use crate::error::ChessError;
+
+
+// Rank numbers count down from rank 8, so rank 0 is the top of the board
+pub const RANK_8: u8 = 0;
+pub const RANK_7: u8 = 1;
+pub const RANK_2: u8 = 6;
+pub const RANK_1: u8 = 7;
+
+pub const FILE_A: u8 = 0;
+pub const FILE_B: u8 = 1;
+pub const FILE_G: u8 = 6;
+pub const FILE_H: u8 = 7;
+
+const FILE_LABELS: [char; 8] = ['a', 'b', 'c', 'd', 'e', 'f', 'g', 'h'];
+const RANK_LABELS: [char; 8] = ['8', '7', '6', '5', '4', '3', '2', '1'];
+
+
+// A space on the board, such as e4; there is no way to make one off the board
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub struct Square {
+    rank: u8,
+    file: u8,
+}
+
+
+impl Display for Square {
+
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}{}", Self::get_file_label(self.file), Self::get_rank_label(self.rank))
+    }
+
+}
+
+
+impl Square {
+
+    pub const A8: Self = Self { rank: 0, file: 0 };
+    pub const B8: Self = Self { rank: 0, file: 1 };
+    pub const C8: Self = Self { rank: 0, file: 2 };
+    pub const D8: Self = Self { rank: 0, file: 3 };
+    pub const E8: Self = Self { rank: 0, file: 4 };
+    pub const F8: Self = Self { rank: 0, file: 5 };
+    pub const G8: Self = Self { rank: 0, file: 6 };
+    pub const H8: Self = Self { rank: 0, file: 7 };
+    pub const A7: Self = Self { rank: 1, file: 0 };
+    pub const B7: Self = Self { rank: 1, file: 1 };
+    pub const C7: Self = Self { rank: 1, file: 2 };
+    pub const D7: Self = Self { rank: 1, file: 3 };
+    pub const E7: Self = Self { rank: 1, file: 4 };
+    pub const F7: Self = Self { rank: 1, file: 5 };
+    pub const G7: Self = Self { rank: 1, file: 6 };
+    pub const H7: Self = Self { rank: 1, file: 7 };
+    pub const A6: Self = Self { rank: 2, file: 0 };
+    pub const B6: Self = Self { rank: 2, file: 1 };
+    pub const C6: Self = Self { rank: 2, file: 2 };
+    pub const D6: Self = Self { rank: 2, file: 3 };
+    pub const E6: Self = Self { rank: 2, file: 4 };
+    pub const F6: Self = Self { rank: 2, file: 5 };
+    pub const G6: Self = Self { rank: 2, file: 6 };
+    pub const H6: Self = Self { rank: 2, file: 7 };
+    pub const A5: Self = Self { rank: 3, file: 0 };
+    pub const B5: Self = Self { rank: 3, file: 1 };
+    pub const C5: Self = Self { rank: 3, file: 2 };
+    pub const D5: Self = Self { rank: 3, file: 3 };
+    pub const E5: Self = Self { rank: 3, file: 4 };
+    pub const F5: Self = Self { rank: 3, file: 5 };
+    pub const G5: Self = Self { rank: 3, file: 6 };
+    pub const H5: Self = Self { rank: 3, file: 7 };
+    pub const A4: Self = Self { rank: 4, file: 0 };
+    pub const B4: Self = Self { rank: 4, file: 1 };
+    pub const C4: Self = Self { rank: 4, file: 2 };
+    pub const D4: Self = Self { rank: 4, file: 3 };
+    pub const E4: Self = Self { rank: 4, file: 4 };
+    pub const F4: Self = Self { rank: 4, file: 5 };
+    pub const G4: Self = Self { rank: 4, file: 6 };
+    pub const H4: Self = Self { rank: 4, file: 7 };
+    pub const A3: Self = Self { rank: 5, file: 0 };
+    pub const B3: Self = Self { rank: 5, file: 1 };
+    pub const C3: Self = Self { rank: 5, file: 2 };
+    pub const D3: Self = Self { rank: 5, file: 3 };
+    pub const E3: Self = Self { rank: 5, file: 4 };
+    pub const F3: Self = Self { rank: 5, file: 5 };
+    pub const G3: Self = Self { rank: 5, file: 6 };
+    pub const H3: Self = Self { rank: 5, file: 7 };
+    pub const A2: Self = Self { rank: 6, file: 0 };
+    pub const B2: Self = Self { rank: 6, file: 1 };
+    pub const C2: Self = Self { rank: 6, file: 2 };
+    pub const D2: Self = Self { rank: 6, file: 3 };
+    pub const E2: Self = Self { rank: 6, file: 4 };
+    pub const F2: Self = Self { rank: 6, file: 5 };
+    pub const G2: Self = Self { rank: 6, file: 6 };
+    pub const H2: Self = Self { rank: 6, file: 7 };
+    pub const A1: Self = Self { rank: 7, file: 0 };
+    pub const B1: Self = Self { rank: 7, file: 1 };
+    pub const C1: Self = Self { rank: 7, file: 2 };
+    pub const D1: Self = Self { rank: 7, file: 3 };
+    pub const E1: Self = Self { rank: 7, file: 4 };
+    pub const F1: Self = Self { rank: 7, file: 5 };
+    pub const G1: Self = Self { rank: 7, file: 6 };
+    pub const H1: Self = Self { rank: 7, file: 7 };
+
+}
+
+
+impl Square {
+
+    // None for a rank or file past the edge of the board
+    pub fn construct(rank: u8, file: u8) -> Option<Self> {
+        if rank < 8 && file < 8 {
+            Some(Self { rank, file })
+        } else {
+            None
+        }
+    }
+
+    // Every space, from a8 across each rank to h1
+    pub fn all() -> impl Iterator<Item = Self> {
+        (0..8).flat_map(|rank| (0..8).map(move |file| Self { rank, file }))
+    }
+
+    // Reads a label such as "e4"
+    pub fn from_label(label: &str) -> Result<Self, ChessError> {
+        let mut labels = label.chars();
+        match (labels.next(), labels.next(), labels.next()) {
+            (Some(file_label), Some(rank_label), None) => Self::from_labels(file_label, rank_label),
+            _ => Err(ChessError::InvalidSquare(String::from(label))),
+        }
+    }
+
+    pub fn from_labels(file_label: char, rank_label: char) -> Result<Self, ChessError> {
+        match (Self::get_rank(rank_label), Self::get_file(file_label)) {
+            (Some(rank), Some(file)) => Ok(Self { rank, file }),
+            _ => Err(ChessError::InvalidSquare(format!("{}{}", file_label, rank_label))),
+        }
+    }
+
+    pub fn rank(&self) -> u8 {
+        self.rank
+    }
+
+    pub fn file(&self) -> u8 {
+        self.file
+    }
+
+    // The position in Square::all, for tables with an entry per space
+    pub fn get_index(&self) -> usize {
+        self.rank as usize * 8 + self.file as usize
+    }
+
+    // The space this many ranks (towards rank 1) and files (towards file h) away, if it is on the board
+    pub fn offset(&self, rank_offset: i8, file_offset: i8) -> Option<Self> {
+        let rank = self.rank.checked_add_signed(rank_offset)?;
+        let file = self.file.checked_add_signed(file_offset)?;
+        Self::construct(rank, file)
+    }
+
+    // The space in this space's rank and the other's file, such as where a pawn captured en passant stood
+    pub fn with_file_of(&self, other: Self) -> Self {
+        Self { rank: self.rank, file: other.file }
+    }
+
+    pub fn get_rank(rank_label: char) -> Option<u8> {
+        RANK_LABELS.iter().position(|label| *label == rank_label).map(|rank| rank as u8)
+    }
+
+    pub fn get_file(file_label: char) -> Option<u8> {
+        FILE_LABELS.iter().position(|label| *label == file_label).map(|file| file as u8)
+    }
+
+    pub fn get_rank_label(rank: u8) -> char {
+        RANK_LABELS[rank as usize]
+    }
+
+    pub fn get_file_label(file: u8) -> char {
+        FILE_LABELS[file as usize]
+    }
+
+}
+
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    #[test]
+    fn reads_and_writes_labels() {
+        let square = Square::from_label("e4").unwrap();
+        assert_eq!((square.rank(), square.file()), (4, 4));
+        assert_eq!(square.to_string(), "e4");
+        assert_eq!(Square::from_label("a8").unwrap().rank(), RANK_8);
+        assert_eq!(Square::from_label("h1").unwrap().file(), FILE_H);
+        for label in ["", "e", "e9", "i4", "e44", "4e"] {
+            assert_eq!(Square::from_label(label), Err(ChessError::InvalidSquare(String::from(label))));
+        }
+    }
+
+    #[test]
+    fn stays_on_the_board() {
+        assert_eq!(Square::construct(4, 4), Some(Square::E4));
+        assert_eq!(Square::construct(8, 0), None);
+        assert_eq!(Square::construct(0, 8), None);
+        assert_eq!(Square::E4.offset(-2, 1), Some(Square::F6));
+        assert_eq!(Square::A1.offset(1, 0), None);
+        assert_eq!(Square::A1.offset(0, -1), None);
+        assert_eq!(Square::H8.offset(-1, 0), None);
+        assert_eq!(Square::E5.with_file_of(Square::D6), Square::D5);
+
+        let squares: Vec<Square> = Square::all().collect();
+        assert_eq!(squares.len(), 64);
+        assert_eq!((squares[0], squares[63]), (Square::A8, Square::H1));
+        assert!(squares.iter().enumerate().all(|(index, square)| square.get_index() == index));
+    }
+
+}
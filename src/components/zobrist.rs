@@ -0,0 +1,154 @@
+// crate::components::zobrist
+
+use crate::components::chess_board::ChessBoard;
+use crate::components::chess_piece::ChessPiece;
+use crate::components::color::Color;
+use crate::components::square::Square;
+
+
+// A key per piece type and color on each space, then black to move, the four castling rights and the eight en passant files
+const PIECE_KEY_COUNT: usize = 2 * 6 * 64;
+const BLACK_TO_MOVE_INDEX: usize = PIECE_KEY_COUNT;
+const CASTLING_INDEX: usize = BLACK_TO_MOVE_INDEX + 1;
+const EN_PASSANT_INDEX: usize = CASTLING_INDEX + 4;
+const KEY_COUNT: usize = EN_PASSANT_INDEX + 8;
+
+// Fixed, so that hashes are the same from run to run
+const SEED: u64 = 0x2545_F491_4F6C_DD1D;
+
+static KEYS: [u64; KEY_COUNT] = generate_keys(SEED);
+
+
+// splitmix64, which is simple enough to run at compile time
+const fn generate_keys(seed: u64) -> [u64; KEY_COUNT] {
+    let mut keys = [0; KEY_COUNT];
+    let mut state = seed;
+    let mut index = 0;
+    while index < KEY_COUNT {
+        state = state.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut key = state;
+        key = (key ^ (key >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        key = (key ^ (key >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        keys[index] = key ^ (key >> 31);
+        index += 1;
+    }
+    return keys;
+}
+
+// The key for a piece on a space; an empty space has none
+pub fn get_piece_key(piece: &ChessPiece, square: Square) -> u64 {
+    match (piece.get_color(), piece.get_type()) {
+        (Some(color), Some(piece_type)) => {
+            KEYS[((color as usize * 6 + piece_type as usize) * 64) + square.get_index()]
+        },
+        _ => 0,
+    }
+}
+
+// The key for everything besides the pieces: the side to move, the castling rights and any en passant file
+pub fn get_state_key(chess_board: &ChessBoard) -> u64 {
+    let mut key = 0;
+    if chess_board.get_turn() == Color::Black {
+        key ^= KEYS[BLACK_TO_MOVE_INDEX];
+    }
+    for (index, has_right) in chess_board.get_castling_rights().into_iter().enumerate() {
+        if has_right {
+            key ^= KEYS[CASTLING_INDEX + index];
+        }
+    }
+    if let Some(file) = get_en_passant_file(chess_board) {
+        key ^= KEYS[EN_PASSANT_INDEX + file as usize];
+    }
+    return key;
+}
+
+// Hashes the position from scratch; boards keep their hash up to date as moves are made instead
+pub fn compute_hash(chess_board: &ChessBoard) -> u64 {
+    let mut hash = get_state_key(chess_board);
+    for square in Square::all() {
+        hash ^= get_piece_key(chess_board.borrow_space_contents(square), square);
+    }
+    return hash;
+}
+
+// The file of a pawn that just moved two spaces, but only if a pawn beside it could capture it en passant,
+// since otherwise the position is the same as if it had gotten there some other way
+fn get_en_passant_file(chess_board: &ChessBoard) -> Option<u8> {
+    let (start, target) = (*chess_board.get_last_move())?;
+    let pawn = chess_board.borrow_space_contents(target);
+    if start.file() != target.file() || start.rank().abs_diff(target.rank()) != 2 || !pawn.is_pawn() {
+        return None;
+    }
+    let turn = chess_board.get_turn();
+    let can_capture = [-1, 1]
+        .into_iter()
+        .filter_map(|file_offset| chess_board.borrow_offset_space_contents(target, 0, file_offset))
+        .any(|piece| piece.is_pawn() && piece.is_color(turn));
+    return if can_capture { Some(target.file()) } else { None };
+}
+
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use crate::utils::logs::with_logs_muted;
+
+    const KIWIPETE: &str = "r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1";
+    const POSITION_4: &str = "r3k2r/Pppp1ppp/1b3nbN/nP6/BBP1P3/q4N2/Pp1P2PP/R2Q1RK1 w kq - 0 1";
+
+    // check the kept hash against one from scratch after every sequence of depth moves, and after taking them back
+    fn assert_hash_kept(chess_board: &mut ChessBoard, depth: u32) {
+        assert_eq!(chess_board.get_hash(), compute_hash(chess_board), "{}", chess_board.to_fen());
+        if depth == 0 {
+            return;
+        }
+        let hash = chess_board.get_hash();
+        for chess_move in chess_board.legal_moves(chess_board.get_turn()) {
+            let undo_record = chess_board.make_move(&chess_move);
+            assert_hash_kept(chess_board, depth - 1);
+            chess_board.unmake_move(&undo_record);
+            assert_eq!(chess_board.get_hash(), hash);
+        }
+    }
+
+    #[test]
+    fn keeps_the_hash_through_every_kind_of_move() {
+        // between them, castling, en passant captures and promotions
+        for fen in [KIWIPETE, POSITION_4, "rnbqkbnr/ppp1p1pp/8/3pPp2/8/8/PPPP1PPP/RNBQKBNR w KQkq f6 0 3"] {
+            let mut chess_board = ChessBoard::from_fen(fen).unwrap();
+            with_logs_muted(|| assert_hash_kept(&mut chess_board, 2));
+        }
+    }
+
+    #[test]
+    fn the_same_position_has_the_same_hash() {
+        let play = |moves: &[(Square, Square)]| {
+            let mut chess_board = ChessBoard::default();
+            for (start, target) in moves {
+                chess_board.move_piece(*start, *target, None).unwrap();
+            }
+            chess_board
+        };
+        // 1. Nf3 Nf6 2. Nc3 against 1. Nc3 Nf6 2. Nf3
+        let knights = play(&[(Square::G1, Square::F3), (Square::G8, Square::F6), (Square::B1, Square::C3)]);
+        assert_eq!(knights.get_hash(), play(&[(Square::B1, Square::C3), (Square::G8, Square::F6), (Square::G1, Square::F3)]).get_hash());
+        assert_ne!(knights.get_hash(), play(&[(Square::B1, Square::C3), (Square::G8, Square::F6)]).get_hash());
+        // the knights back home again
+        let returned = play(&[(Square::G1, Square::F3), (Square::G8, Square::F6), (Square::F3, Square::G1), (Square::F6, Square::G8)]);
+        assert_eq!(returned.get_hash(), ChessBoard::default().get_hash());
+        // the same placement, but the rook has moved
+        let rook_moved = play(&[(Square::G1, Square::F3), (Square::G8, Square::F6), (Square::H1, Square::G1), (Square::F6, Square::G8), (Square::G1, Square::H1), (Square::G8, Square::F6)]);
+        let rook_home = play(&[(Square::G1, Square::F3), (Square::G8, Square::F6), (Square::F3, Square::G1), (Square::F6, Square::G8), (Square::G1, Square::F3), (Square::G8, Square::F6)]);
+        assert_ne!(rook_moved.get_hash(), rook_home.get_hash());
+    }
+
+    #[test]
+    fn en_passant_counts_only_when_it_can_be_played() {
+        let without_target = ChessBoard::from_fen("4k3/8/8/8/3Pp3/8/8/4K3 b - - 0 1").unwrap();
+        assert_ne!(ChessBoard::from_fen("4k3/8/8/8/3Pp3/8/8/4K3 b - d3 0 1").unwrap().get_hash(), without_target.get_hash());
+        let without_target = ChessBoard::from_fen("4k3/8/8/8/3P4/8/8/4K3 b - - 0 1").unwrap();
+        assert_eq!(ChessBoard::from_fen("4k3/8/8/8/3P4/8/8/4K3 b - d3 0 1").unwrap().get_hash(), without_target.get_hash());
+    }
+
+}
@@ -0,0 +1,135 @@
+// crate::engine::eval
+
+use crate::components::chess_board::ChessBoard;
+use crate::components::color::Color;
+use crate::components::piece_type::PieceType;
+use crate::components::square::Square;
+
+// Piece-square tables, in centipawns, from white's side of the board: row 0 is rank 8, as in ChessBoard
+type PieceSquareTable = [[i32; 8]; 8];
+
+const PAWN_TABLE: PieceSquareTable = [
+    [  0,   0,   0,   0,   0,   0,   0,   0],
+    [ 50,  50,  50,  50,  50,  50,  50,  50],
+    [ 10,  10,  20,  30,  30,  20,  10,  10],
+    [  5,   5,  10,  25,  25,  10,   5,   5],
+    [  0,   0,   0,  20,  20,   0,   0,   0],
+    [  5,  -5, -10,   0,   0, -10,  -5,   5],
+    [  5,  10,  10, -20, -20,  10,  10,   5],
+    [  0,   0,   0,   0,   0,   0,   0,   0],
+];
+
+const KNIGHT_TABLE: PieceSquareTable = [
+    [-50, -40, -30, -30, -30, -30, -40, -50],
+    [-40, -20,   0,   0,   0,   0, -20, -40],
+    [-30,   0,  10,  15,  15,  10,   0, -30],
+    [-30,   5,  15,  20,  20,  15,   5, -30],
+    [-30,   0,  15,  20,  20,  15,   0, -30],
+    [-30,   5,  10,  15,  15,  10,   5, -30],
+    [-40, -20,   0,   5,   5,   0, -20, -40],
+    [-50, -40, -30, -30, -30, -30, -40, -50],
+];
+
+const BISHOP_TABLE: PieceSquareTable = [
+    [-20, -10, -10, -10, -10, -10, -10, -20],
+    [-10,   0,   0,   0,   0,   0,   0, -10],
+    [-10,   0,   5,  10,  10,   5,   0, -10],
+    [-10,   5,   5,  10,  10,   5,   5, -10],
+    [-10,   0,  10,  10,  10,  10,   0, -10],
+    [-10,  10,  10,  10,  10,  10,  10, -10],
+    [-10,   5,   0,   0,   0,   0,   5, -10],
+    [-20, -10, -10, -10, -10, -10, -10, -20],
+];
+
+const ROOK_TABLE: PieceSquareTable = [
+    [  0,   0,   0,   0,   0,   0,   0,   0],
+    [  5,  10,  10,  10,  10,  10,  10,   5],
+    [ -5,   0,   0,   0,   0,   0,   0,  -5],
+    [ -5,   0,   0,   0,   0,   0,   0,  -5],
+    [ -5,   0,   0,   0,   0,   0,   0,  -5],
+    [ -5,   0,   0,   0,   0,   0,   0,  -5],
+    [ -5,   0,   0,   0,   0,   0,   0,  -5],
+    [  0,   0,   0,   5,   5,   0,   0,   0],
+];
+
+const QUEEN_TABLE: PieceSquareTable = [
+    [-20, -10, -10,  -5,  -5, -10, -10, -20],
+    [-10,   0,   0,   0,   0,   0,   0, -10],
+    [-10,   0,   5,   5,   5,   5,   0, -10],
+    [ -5,   0,   5,   5,   5,   5,   0,  -5],
+    [  0,   0,   5,   5,   5,   5,   0,  -5],
+    [-10,   5,   5,   5,   5,   5,   0, -10],
+    [-10,   0,   5,   0,   0,   0,   0, -10],
+    [-20, -10, -10,  -5,  -5, -10, -10, -20],
+];
+
+// the king hides behind its pawns while there are pieces around to attack it
+const KING_TABLE: PieceSquareTable = [
+    [-30, -40, -40, -50, -50, -40, -40, -30],
+    [-30, -40, -40, -50, -50, -40, -40, -30],
+    [-30, -40, -40, -50, -50, -40, -40, -30],
+    [-30, -40, -40, -50, -50, -40, -40, -30],
+    [-20, -30, -30, -40, -40, -30, -30, -20],
+    [-10, -20, -20, -20, -20, -20, -20, -10],
+    [ 20,  20,   0,   0,   0,   0,  20,  20],
+    [ 20,  30,  10,   0,   0,  10,  30,  20],
+];
+
+
+pub fn get_piece_value(piece_type: PieceType) -> i32 {
+    match piece_type {
+        PieceType::Pawn => 100,
+        PieceType::Knight => 320,
+        PieceType::Bishop => 330,
+        PieceType::Rook => 500,
+        PieceType::Queen => 900,
+        PieceType::King => 0,
+    }
+}
+
+fn get_square_value(piece_type: PieceType, color: Color, square: Square) -> i32 {
+    let table = match piece_type {
+        PieceType::Pawn => &PAWN_TABLE,
+        PieceType::Knight => &KNIGHT_TABLE,
+        PieceType::Bishop => &BISHOP_TABLE,
+        PieceType::Rook => &ROOK_TABLE,
+        PieceType::Queen => &QUEEN_TABLE,
+        PieceType::King => &KING_TABLE,
+    };
+    // black reads the tables from its own side of the board
+    let row = match color {
+        Color::White => square.rank(),
+        Color::Black => 7 - square.rank(),
+    };
+    table[row as usize][square.file() as usize]
+}
+
+// Material plus piece-square bonuses, in centipawns, from the perspective of the side to move
+pub fn evaluate(chess_board: &ChessBoard) -> i32 {
+    let mut score = 0;
+    for square in Square::all() {
+        let piece = chess_board.borrow_space_contents(square);
+        if let (Some(color), Some(piece_type)) = (piece.get_color(), piece.get_type()) {
+            let value = get_piece_value(piece_type) + get_square_value(piece_type, color, square);
+            score += if color == chess_board.get_turn() { value } else { -value };
+        }
+    }
+    return score;
+}
+
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    #[test]
+    fn mirrored_positions_score_the_same_for_either_side() {
+        assert_eq!(evaluate(&ChessBoard::default()), 0);
+        let white = ChessBoard::from_fen("4k3/8/8/8/4P3/2N5/8/4K3 w - - 0 1").unwrap();
+        let black = ChessBoard::from_fen("4k3/8/2n5/4p3/8/8/8/4K3 b - - 0 1").unwrap();
+        assert!(evaluate(&white) > 400);
+        assert_eq!(evaluate(&white), evaluate(&black));
+    }
+
+}
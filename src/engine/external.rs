@@ -0,0 +1,210 @@
+// crate::engine::external
+
+use std::io::BufRead;
+use std::io::BufReader;
+use std::io::Write;
+use std::path::Path;
+use std::process::Child;
+use std::process::ChildStdin;
+use std::process::Command;
+use std::process::Stdio;
+use std::sync::mpsc::Receiver;
+use std::sync::mpsc::RecvTimeoutError;
+use std::time::Duration;
+use std::time::Instant;
+
+use crate::components::chess_board::ChessBoard;
+use crate::components::chess_move::ChessMove;
+use crate::engine::search::MAX_DEPTH;
+use crate::engine::search::SearchLimits;
+use crate::error::ChessError;
+use crate::notation::coordinate::parse_coordinate;
+use crate::notation::coordinate::to_coordinate;
+use crate::utils::logs::log;
+use crate::utils::logs::with_logs_muted;
+
+// how long the engine gets to answer "uci" and "isready"
+const HANDSHAKE_TIMEOUT: Duration = Duration::from_secs(10);
+// how long the engine gets to exit after "quit" before it is killed
+const QUIT_TIMEOUT: Duration = Duration::from_millis(500);
+// how long past its time limit the engine gets to send its move
+const MOVE_GRACE: Duration = Duration::from_secs(5);
+// how long the engine gets to send its move when searching without a time limit
+const UNTIMED_MOVE_TIMEOUT: Duration = Duration::from_secs(120);
+
+
+// A UCI engine running as a child process
+pub struct ExternalEngine {
+    name: String,
+    child: Child,
+    stdin: ChildStdin,
+    // lines from the engine's stdout, read on their own thread
+    lines: Receiver<String>,
+    // the position the engine is searching, to read its best move against
+    search_board: Option<ChessBoard>,
+    // when the engine's search is given up on if it still hasn't sent its move
+    search_deadline: Instant,
+    // best moves still to come from cancelled searches
+    ignored_best_moves: u32,
+}
+
+
+impl ExternalEngine {
+
+    // Starts the engine and waits for it to finish the UCI handshake
+    pub fn spawn(path: &Path) -> Result<Self, ChessError> {
+        let context = format!("Couldn't start the engine {}", path.display());
+        let mut child = Command::new(path)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .spawn()
+            .map_err(|error| ChessError::from(error).with_context(&context))?;
+        let stdin = child.stdin.take().unwrap();
+        let stdout = BufReader::new(child.stdout.take().unwrap());
+        let (sender, lines) = std::sync::mpsc::channel();
+        std::thread::spawn(move || {
+            for line in stdout.lines().map_while(Result::ok) {
+                if sender.send(line).is_err() {
+                    break;
+                }
+            }
+        });
+
+        let mut engine = Self {
+            name: path.display().to_string(),
+            child,
+            stdin,
+            lines,
+            search_board: None,
+            search_deadline: Instant::now(),
+            ignored_best_moves: 0,
+        };
+        engine.send("uci")?;
+        for line in engine.read_until("uciok")? {
+            if let Some(name) = line.strip_prefix("id name ") {
+                engine.name = String::from(name);
+            }
+        }
+        engine.send("isready")?;
+        engine.read_until("readyok")?;
+        log("INFO", format!("Started the engine {}.", engine.name));
+        return Ok(engine);
+    }
+
+    pub fn get_name(&self) -> &str {
+        &self.name
+    }
+
+    // Starts searching the position reached by playing moves from start_board; the move comes from receive_best_move
+    pub fn go(&mut self, start_board: &ChessBoard, moves: &[ChessMove], limits: SearchLimits) -> Result<(), ChessError> {
+        let mut position = format!("position fen {}", start_board.to_fen());
+        if !moves.is_empty() {
+            position.push_str(" moves");
+        }
+        let mut search_board = start_board.clone();
+        for chess_move in moves {
+            position.push(' ');
+            position.push_str(&to_coordinate(chess_move));
+            with_logs_muted(|| search_board.make_move(chess_move));
+        }
+        let mut go = String::from("go");
+        if limits.max_depth < MAX_DEPTH {
+            go.push_str(&format!(" depth {}", limits.max_depth));
+        }
+        match limits.time_limit {
+            Some(time_limit) => go.push_str(&format!(" movetime {}", time_limit.as_millis())),
+            None if limits.max_depth >= MAX_DEPTH => go.push_str(" infinite"),
+            None => (),
+        }
+        self.send(&position)?;
+        self.send(&go)?;
+        self.search_board = Some(search_board);
+        self.search_deadline = Instant::now() + limits.time_limit.map_or(UNTIMED_MOVE_TIMEOUT, |time_limit| time_limit + MOVE_GRACE);
+        return Ok(());
+    }
+
+    // The engine's move once it has one, waiting up to wait for it; the move must be legal in the searched position.
+    // An engine that is well past its time is told to stop and given up on with an error.
+    pub fn receive_best_move(&mut self, wait: Duration) -> Result<Option<ChessMove>, ChessError> {
+        let deadline = Instant::now() + wait;
+        loop {
+            let line = match self.lines.recv_timeout(deadline.saturating_duration_since(Instant::now())) {
+                Ok(line) => line,
+                Err(RecvTimeoutError::Timeout) if self.search_board.is_some() && Instant::now() >= self.search_deadline => {
+                    self.cancel();
+                    return Err(ChessError::Io(format!("The engine {} didn't send its move in time.", self.name)));
+                },
+                Err(RecvTimeoutError::Timeout) => return Ok(None),
+                Err(RecvTimeoutError::Disconnected) => {
+                    return Err(ChessError::Io(format!("The engine {} has exited.", self.name)));
+                }
+            };
+            let coordinate = match line.strip_prefix("bestmove") {
+                Some(rest) => rest.split_whitespace().next().unwrap_or("0000").to_string(),
+                None => continue,
+            };
+            if self.ignored_best_moves > 0 {
+                self.ignored_best_moves -= 1;
+                continue;
+            }
+            let search_board = match self.search_board.take() {
+                Some(search_board) => search_board,
+                None => continue,
+            };
+            return parse_coordinate(&search_board, &coordinate)
+                .map(Some)
+                .map_err(|error| error.with_context(&format!("The engine {} played a bad move", self.name)));
+        }
+    }
+
+    // Stops the search without waiting for the engine, ignoring the move it sends back
+    pub fn cancel(&mut self) {
+        if self.search_board.take().is_some() {
+            self.ignored_best_moves += 1;
+            if let Err(error) = self.send("stop") {
+                log("ERROR", format!("{}", error));
+            }
+        }
+    }
+
+    fn send(&mut self, command: &str) -> Result<(), ChessError> {
+        writeln!(self.stdin, "{}", command)
+            .and_then(|_| self.stdin.flush())
+            .map_err(|error| ChessError::from(error).with_context(&format!("Couldn't send \"{}\" to the engine {}", command, self.name)))
+    }
+
+    // the lines the engine sends up to and including expected, waiting no longer than HANDSHAKE_TIMEOUT
+    fn read_until(&mut self, expected: &str) -> Result<Vec<String>, ChessError> {
+        let deadline = Instant::now() + HANDSHAKE_TIMEOUT;
+        let mut lines = vec![];
+        loop {
+            match self.lines.recv_timeout(deadline.saturating_duration_since(Instant::now())) {
+                Ok(line) if line.trim() == expected => return Ok(lines),
+                Ok(line) => lines.push(line),
+                Err(_) => {
+                    return Err(ChessError::Io(format!("The engine {} didn't answer with \"{}\".", self.name, expected)));
+                }
+            }
+        }
+    }
+
+}
+
+
+impl Drop for ExternalEngine {
+
+    // ask the engine to quit, and make sure of it if it doesn't
+    fn drop(&mut self) {
+        let _ = self.send("quit");
+        let deadline = Instant::now() + QUIT_TIMEOUT;
+        while Instant::now() < deadline {
+            if let Ok(Some(_)) = self.child.try_wait() {
+                return;
+            }
+            std::thread::sleep(Duration::from_millis(10));
+        }
+        let _ = self.child.kill();
+        let _ = self.child.wait();
+    }
+
+}
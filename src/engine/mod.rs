@@ -0,0 +1,6 @@
+// crate::engine
+
+pub mod eval;
+pub mod external;
+pub mod search;
+pub mod transposition;
@@ -0,0 +1,412 @@
+// crate::engine::search
+
+use std::sync::Arc;
+use std::sync::atomic::AtomicBool;
+use std::sync::atomic::Ordering;
+use std::thread::JoinHandle;
+use std::time::Duration;
+use std::time::Instant;
+
+use crate::components::chess_board::ChessBoard;
+use crate::components::chess_move::ChessMove;
+use crate::components::chess_move::MoveKind;
+use crate::engine::eval::evaluate;
+use crate::engine::eval::get_piece_value;
+use crate::engine::transposition::Bound;
+use crate::engine::transposition::Entry;
+use crate::engine::transposition::TranspositionTable;
+use crate::utils::logs::with_logs_muted;
+
+// scores at or beyond MATE_SCORE - MAX_DEPTH are forced mates, nearer mates scoring higher
+pub const MATE_SCORE: i32 = 100_000;
+pub const MAX_DEPTH: u32 = 64;
+// how many nodes go by between looking at the clock and the stop flag
+const NODES_PER_CHECK: u64 = 1024;
+
+
+// When to stop searching: after max_depth, or once time_limit has passed, whichever comes first
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct SearchLimits {
+    pub max_depth: u32,
+    pub time_limit: Option<Duration>,
+}
+
+
+impl Default for SearchLimits {
+
+    fn default() -> Self {
+        Self {
+            max_depth: MAX_DEPTH,
+            time_limit: Some(Duration::from_secs(1)),
+        }
+    }
+
+}
+
+
+// What the search knows after completing a depth
+#[derive(Clone, Debug)]
+pub struct SearchInfo {
+    pub depth: u32,
+    // centipawns for the side to move (see MATE_SCORE)
+    pub score: i32,
+    pub nodes: u64,
+    pub elapsed: Duration,
+    pub best_move: ChessMove,
+}
+
+
+// Searches with iterative deepening until the limits are reached or stop is set, calling on_depth after each
+// completed depth; gives the best move found, or None if there are no legal moves.
+// The transposition table keeps what was learned, for later depths and later searches.
+pub fn search(
+    chess_board: &ChessBoard,
+    limits: SearchLimits,
+    transposition_table: &mut TranspositionTable,
+    stop: &AtomicBool,
+    mut on_depth: impl FnMut(&SearchInfo)
+) -> Option<ChessMove> {
+    with_logs_muted(|| {
+        let mut searcher = Searcher {
+            start_time: Instant::now(),
+            deadline: limits.time_limit.map(|time_limit| Instant::now() + time_limit),
+            stop,
+            transposition_table,
+            nodes: 0,
+            is_aborted: false,
+        };
+        let mut board = chess_board.clone();
+        let mut root_moves = board.legal_moves(board.get_turn());
+        if root_moves.is_empty() {
+            return None;
+        }
+        let hash_move = searcher.transposition_table.probe(board.get_hash()).and_then(|entry| entry.best_move.clone());
+        order_moves(&board, &mut root_moves, hash_move.as_ref());
+        let mut best_move = root_moves[0].clone();
+
+        for depth in 1..=limits.max_depth.clamp(1, MAX_DEPTH) {
+            let mut alpha = -MATE_SCORE - 1;
+            let mut depth_best: Option<ChessMove> = None;
+            for chess_move in &root_moves {
+                let undo_record = board.make_move(chess_move);
+                let score = -searcher.negamax(&mut board, depth - 1, 1, -MATE_SCORE - 1, -alpha);
+                board.unmake_move(&undo_record);
+                if searcher.is_aborted {
+                    break;
+                }
+                if score > alpha {
+                    alpha = score;
+                    depth_best = Some(chess_move.clone());
+                }
+            }
+            // an unfinished depth is thrown away, leaving the best move of the last finished one
+            if searcher.is_aborted {
+                break;
+            }
+            if let Some(depth_best) = depth_best {
+                best_move = depth_best;
+            }
+            searcher.transposition_table.store(Entry {
+                hash: board.get_hash(),
+                depth,
+                score: alpha,
+                bound: Bound::Exact,
+                best_move: Some(best_move.clone()),
+            });
+            on_depth(&SearchInfo {
+                depth,
+                score: alpha,
+                nodes: searcher.nodes,
+                elapsed: searcher.start_time.elapsed(),
+                best_move: best_move.clone(),
+            });
+            // search the best move first next time, and stop early once a forced mate is found
+            let index = root_moves.iter().position(|chess_move| *chess_move == best_move).unwrap();
+            let chess_move = root_moves.remove(index);
+            root_moves.insert(0, chess_move);
+            if alpha.abs() >= MATE_SCORE - MAX_DEPTH as i32 {
+                break;
+            }
+        }
+        return Some(best_move);
+    })
+}
+
+// The best move within the default limits
+pub fn choose_move(chess_board: &ChessBoard) -> Option<ChessMove> {
+    search(chess_board, SearchLimits::default(), &mut TranspositionTable::default(), &AtomicBool::new(false), |_| ())
+}
+
+
+// A search running on its own thread, so that e.g. the window stays responsive while the engine thinks
+pub struct BackgroundSearch {
+    handle: JoinHandle<Option<ChessMove>>,
+    stop: Arc<AtomicBool>,
+}
+
+
+impl BackgroundSearch {
+
+    pub fn start(chess_board: ChessBoard, limits: SearchLimits) -> Self {
+        let stop = Arc::new(AtomicBool::new(false));
+        let thread_stop = Arc::clone(&stop);
+        let handle = std::thread::spawn(move || {
+            search(&chess_board, limits, &mut TranspositionTable::default(), &thread_stop, |_| ())
+        });
+        Self {
+            handle,
+            stop,
+        }
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.handle.is_finished()
+    }
+
+    // wait for the search (if still running) and take its move
+    pub fn finish(self) -> Option<ChessMove> {
+        self.handle.join().unwrap_or(None)
+    }
+
+    // tell the search to stop without waiting for it
+    pub fn cancel(self) {
+        self.stop.store(true, Ordering::Relaxed);
+    }
+
+}
+
+
+struct Searcher<'a> {
+    start_time: Instant,
+    deadline: Option<Instant>,
+    stop: &'a AtomicBool,
+    transposition_table: &'a mut TranspositionTable,
+    nodes: u64,
+    is_aborted: bool,
+}
+
+
+impl Searcher<'_> {
+
+    // the score of the position for the side to move, looking depth moves ahead and then at captures only
+    fn negamax(&mut self, board: &mut ChessBoard, depth: u32, ply: u32, mut alpha: i32, beta: i32) -> i32 {
+        if self.should_abort() {
+            return 0;
+        }
+        if depth == 0 {
+            return self.quiesce(board, ply, alpha, beta);
+        }
+        self.nodes += 1;
+
+        // a search of this position at least as deep may already settle it
+        let hash = board.get_hash();
+        let mut hash_move: Option<ChessMove> = None;
+        if let Some(entry) = self.transposition_table.probe(hash) {
+            let score = from_table_score(entry.score, ply);
+            if entry.depth >= depth {
+                match entry.bound {
+                    Bound::Exact => return score,
+                    Bound::Lower if score >= beta => return beta,
+                    Bound::Upper if score <= alpha => return alpha,
+                    _ => (),
+                }
+            }
+            hash_move = entry.best_move.clone();
+        }
+
+        let mut moves = board.legal_moves(board.get_turn());
+        if moves.is_empty() {
+            return if board.is_in_check(board.get_turn()) { -MATE_SCORE + ply as i32 } else { 0 };
+        }
+        order_moves(board, &mut moves, hash_move.as_ref());
+        let mut entry = Entry { hash, depth, score: alpha, bound: Bound::Upper, best_move: None };
+        for chess_move in &moves {
+            let undo_record = board.make_move(chess_move);
+            let score = -self.negamax(board, depth - 1, ply + 1, -beta, -alpha);
+            board.unmake_move(&undo_record);
+            if self.is_aborted {
+                return 0;
+            }
+            if score >= beta {
+                entry = Entry { score: beta, bound: Bound::Lower, best_move: Some(chess_move.clone()), ..entry };
+                break;
+            }
+            if score > alpha {
+                alpha = score;
+                entry = Entry { score: alpha, bound: Bound::Exact, best_move: Some(chess_move.clone()), ..entry };
+            }
+        }
+        let score = entry.score;
+        entry.score = to_table_score(score, ply);
+        self.transposition_table.store(entry);
+        return score;
+    }
+
+    // follows captures and promotions until the position is quiet, so that exchanges aren't cut off halfway
+    fn quiesce(&mut self, board: &mut ChessBoard, ply: u32, mut alpha: i32, beta: i32) -> i32 {
+        if self.should_abort() {
+            return 0;
+        }
+        self.nodes += 1;
+
+        let mut moves = board.legal_moves(board.get_turn());
+        if moves.is_empty() {
+            return if board.is_in_check(board.get_turn()) { -MATE_SCORE + ply as i32 } else { 0 };
+        }
+        // the side to move can usually do at least as well as standing pat
+        let stand_pat = evaluate(board);
+        if stand_pat >= beta {
+            return beta;
+        }
+        if stand_pat > alpha {
+            alpha = stand_pat;
+        }
+        moves.retain(|chess_move| is_capture(board, chess_move) || chess_move.get_promotion().is_some());
+        order_moves(board, &mut moves, None);
+        for chess_move in &moves {
+            let undo_record = board.make_move(chess_move);
+            let score = -self.quiesce(board, ply + 1, -beta, -alpha);
+            board.unmake_move(&undo_record);
+            if self.is_aborted {
+                return 0;
+            }
+            if score >= beta {
+                return beta;
+            }
+            if score > alpha {
+                alpha = score;
+            }
+        }
+        return alpha;
+    }
+
+    fn should_abort(&mut self) -> bool {
+        if !self.is_aborted && self.nodes.is_multiple_of(NODES_PER_CHECK) {
+            self.is_aborted = self.stop.load(Ordering::Relaxed)
+                || self.deadline.is_some_and(|deadline| Instant::now() >= deadline);
+        }
+        self.is_aborted
+    }
+
+}
+
+
+fn is_capture(board: &ChessBoard, chess_move: &ChessMove) -> bool {
+    chess_move.kind == MoveKind::EnPassant
+        || !board.borrow_space_contents(chess_move.target).is_empty()
+}
+
+// Mate scores are kept in the table as distances from the position rather than from the root,
+// since the same position can come up at different plies
+fn to_table_score(score: i32, ply: u32) -> i32 {
+    if score >= MATE_SCORE - MAX_DEPTH as i32 {
+        return score + ply as i32;
+    }
+    if score <= -MATE_SCORE + MAX_DEPTH as i32 {
+        return score - ply as i32;
+    }
+    return score;
+}
+
+fn from_table_score(score: i32, ply: u32) -> i32 {
+    if score >= MATE_SCORE - MAX_DEPTH as i32 {
+        return score - ply as i32;
+    }
+    if score <= -MATE_SCORE + MAX_DEPTH as i32 {
+        return score + ply as i32;
+    }
+    return score;
+}
+
+// Likely good moves first: the best move found here before, then promotions,
+// then captures of the most valuable pieces by the least valuable ones
+fn order_moves(board: &ChessBoard, moves: &mut [ChessMove], hash_move: Option<&ChessMove>) {
+    moves.sort_by_cached_key(|chess_move| {
+        if Some(chess_move) == hash_move {
+            return i32::MIN;
+        }
+        let mut priority = 0;
+        if let Some(piece_type) = chess_move.get_promotion() {
+            priority += get_piece_value(piece_type);
+        }
+        if is_capture(board, chess_move) {
+            let victim = board.borrow_space_contents(chess_move.target)
+                .get_type()
+                .map_or(100, get_piece_value);
+            let attacker = board.borrow_space_contents(chess_move.start)
+                .get_type()
+                .map_or(0, get_piece_value);
+            priority += 10 * victim - attacker / 10;
+        }
+        -priority
+    });
+}
+
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use crate::components::square::Square;
+
+    fn search_to_depth(fen: &str, max_depth: u32) -> Option<ChessMove> {
+        let chess_board = ChessBoard::from_fen(fen).unwrap();
+        let limits = SearchLimits { max_depth, time_limit: None };
+        search(&chess_board, limits, &mut TranspositionTable::construct(1), &AtomicBool::new(false), |_| ())
+    }
+
+    fn get_target(chess_move: Option<ChessMove>) -> Option<Square> {
+        chess_move.map(|chess_move| chess_move.target)
+    }
+
+    #[test]
+    fn takes_free_material_and_finds_mates() {
+        assert_eq!(get_target(search_to_depth("4k3/8/8/3q4/8/8/3R4/4K3 w - - 0 1", 2)), Some(Square::D5));
+        assert_eq!(get_target(search_to_depth("6k1/5ppp/8/8/8/8/8/R5K1 w - - 0 1", 2)), Some(Square::A8));
+        assert_eq!(search_to_depth("7k/5Q2/6K1/8/8/8/8/8 b - - 0 1", 2), None);
+    }
+
+    #[test]
+    fn avoids_losing_material_to_a_recapture() {
+        // the d5 pawn is defended, so the queen shouldn't take it
+        let chess_move = search_to_depth("4k3/8/2p5/3p4/8/8/3Q4/4K3 w - - 0 1", 2).unwrap();
+        assert_ne!(chess_move.target, Square::D5);
+    }
+
+    #[test]
+    fn reports_each_depth_and_stops_when_asked() {
+        let chess_board = ChessBoard::default();
+        let mut depths = vec![];
+        let limits = SearchLimits { max_depth: 3, time_limit: None };
+        let transposition_table = &mut TranspositionTable::construct(1);
+        assert!(search(&chess_board, limits, transposition_table, &AtomicBool::new(false), |info| depths.push(info.depth)).is_some());
+        assert_eq!(depths, vec![1, 2, 3]);
+        // a stopped search still gives a move
+        assert!(search(&chess_board, SearchLimits::default(), transposition_table, &AtomicBool::new(true), |_| ()).is_some());
+    }
+
+    #[test]
+    fn reuses_the_transposition_table() {
+        let chess_board = ChessBoard::from_fen("4k3/8/2p5/3p4/8/8/3Q4/4K3 w - - 0 1").unwrap();
+        let limits = SearchLimits { max_depth: 3, time_limit: None };
+        let transposition_table = &mut TranspositionTable::construct(1);
+        let mut first_nodes = 0;
+        let first = search(&chess_board, limits, transposition_table, &AtomicBool::new(false), |info| first_nodes = info.nodes);
+        let entry = transposition_table.probe(chess_board.get_hash()).unwrap();
+        assert_eq!((entry.depth, entry.bound, &entry.best_move), (3, Bound::Exact, &first));
+
+        // the same search again finds the same move with less work
+        let mut second_nodes = 0;
+        let second = search(&chess_board, limits, transposition_table, &AtomicBool::new(false), |info| second_nodes = info.nodes);
+        assert_eq!(second, first);
+        assert!(second_nodes < first_nodes);
+    }
+
+    #[test]
+    fn background_searches_finish_with_a_move() {
+        let limits = SearchLimits { max_depth: 2, time_limit: None };
+        let background_search = BackgroundSearch::start(ChessBoard::default(), limits);
+        assert!(background_search.finish().is_some());
+    }
+
+}
@@ -0,0 +1,122 @@
+// crate::engine::transposition
+
+use crate::components::chess_move::ChessMove;
+
+pub const DEFAULT_MEGABYTES: u64 = 16;
+
+
+// How a stored score relates to the true score of the position
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Bound {
+    Exact,
+    // the search failed high, so the true score is at least this
+    Lower,
+    // the search failed low, so the true score is at most this
+    Upper,
+}
+
+
+// What a search learned about a position
+#[derive(Clone, Debug)]
+pub struct Entry {
+    // the whole hash, to tell positions sharing a slot apart
+    pub hash: u64,
+    pub depth: u32,
+    pub score: i32,
+    pub bound: Bound,
+    pub best_move: Option<ChessMove>,
+}
+
+
+// Search results by Zobrist hash, in a fixed number of slots where newer results replace older ones
+pub struct TranspositionTable {
+    entries: Vec<Option<Entry>>,
+}
+
+
+impl Default for TranspositionTable {
+
+    fn default() -> Self {
+        Self::construct(DEFAULT_MEGABYTES)
+    }
+
+}
+
+
+impl TranspositionTable {
+
+    // as many entries as fit in the given size, but at least one
+    pub fn construct(megabytes: u64) -> Self {
+        let count = (megabytes * 1024 * 1024) as usize / size_of::<Option<Entry>>();
+        Self {
+            entries: vec![None; count.max(1)],
+        }
+    }
+
+    pub fn get_capacity(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn probe(&self, hash: u64) -> Option<&Entry> {
+        self.entries[self.get_index(hash)].as_ref().filter(|entry| entry.hash == hash)
+    }
+
+    // keeps a deeper result for the same position, otherwise replaces whatever is in the slot
+    pub fn store(&mut self, entry: Entry) {
+        let index = self.get_index(entry.hash);
+        if let Some(existing) = &self.entries[index]
+        && existing.hash == entry.hash
+        && existing.depth > entry.depth {
+            return;
+        }
+        self.entries[index] = Some(entry);
+    }
+
+    pub fn clear(&mut self) {
+        self.entries.fill(None);
+    }
+
+    fn get_index(&self, hash: u64) -> usize {
+        (hash % self.entries.len() as u64) as usize
+    }
+
+}
+
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    fn get_entry(hash: u64, depth: u32) -> Entry {
+        Entry { hash, depth, score: depth as i32, bound: Bound::Exact, best_move: None }
+    }
+
+    #[test]
+    fn sizes_itself_by_megabytes() {
+        assert_eq!(TranspositionTable::construct(1).get_capacity(), 1024 * 1024 / size_of::<Option<Entry>>());
+        assert_eq!(TranspositionTable::construct(4).get_capacity(), 4 * 1024 * 1024 / size_of::<Option<Entry>>());
+        assert_eq!(TranspositionTable::construct(0).get_capacity(), 1);
+    }
+
+    #[test]
+    fn finds_only_the_position_stored() {
+        let mut transposition_table = TranspositionTable::construct(1);
+        let capacity = transposition_table.get_capacity() as u64;
+        transposition_table.store(get_entry(7, 3));
+        assert_eq!(transposition_table.probe(7).unwrap().depth, 3);
+        // the same slot, but a different position
+        assert!(transposition_table.probe(7 + capacity).is_none());
+
+        // a shallower result for the same position doesn't replace a deeper one, but one for another position does
+        transposition_table.store(get_entry(7, 2));
+        assert_eq!(transposition_table.probe(7).unwrap().depth, 3);
+        transposition_table.store(get_entry(7 + capacity, 1));
+        assert!(transposition_table.probe(7).is_none());
+        assert_eq!(transposition_table.probe(7 + capacity).unwrap().depth, 1);
+
+        transposition_table.clear();
+        assert!(transposition_table.probe(7 + capacity).is_none());
+    }
+
+}
@@ -0,0 +1,56 @@
+// crate::error
+
+use std::fmt::Display;
+
+
+// Everything that can go wrong when handing the crate outside input
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub enum ChessError {
+    InvalidSquare(String),
+    InvalidPieceLabel(String),
+    IllegalMove(String),
+    Parse(String),
+    Io(String),
+}
+
+
+impl Display for ChessError {
+
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::InvalidSquare(label) => write!(f, "Invalid square \"{}\".", label),
+            Self::InvalidPieceLabel(label) => write!(f, "Invalid piece label \"{}\".", label),
+            Self::IllegalMove(message) => write!(f, "{}", message),
+            Self::Parse(message) => write!(f, "{}", message),
+            Self::Io(message) => write!(f, "{}", message),
+        }
+    }
+
+}
+
+
+impl std::error::Error for ChessError {}
+
+
+impl From<std::io::Error> for ChessError {
+
+    fn from(error: std::io::Error) -> Self {
+        Self::Io(error.to_string())
+    }
+
+}
+
+
+impl ChessError {
+
+    // The same error, with where it happened (e.g. "Game 2, move 14...") put in front
+    pub fn with_context(self, context: &str) -> Self {
+        let message = format!("{}: {}", context, self);
+        match self {
+            Self::InvalidSquare(_) | Self::InvalidPieceLabel(_) | Self::Parse(_) => Self::Parse(message),
+            Self::IllegalMove(_) => Self::IllegalMove(message),
+            Self::Io(_) => Self::Io(message),
+        }
+    }
+
+}
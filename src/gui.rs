@@ -0,0 +1,905 @@
+// crate::gui
+
+use std::collections::HashMap;
+use std::time::Duration;
+
+use ggez::Context;
+use ggez::ContextBuilder;
+use ggez::GameResult;
+use ggez::conf;
+use ggez::event;
+use ggez::graphics;
+use ggez::input::keyboard::KeyCode;
+use ggez::input::keyboard::KeyInput;
+use ggez::input::keyboard::KeyMods;
+
+use chess::components::chess_board::ChessBoard;
+use chess::components::chess_board::PROMOTION_TYPES;
+use chess::components::chess_game::ChessGame;
+use chess::components::chess_move::ChessMove;
+use chess::components::chess_move::MoveKind;
+use chess::components::color::Color;
+use chess::components::piece_type::PieceType;
+use chess::components::square::RANK_8;
+use chess::components::square::Square;
+use chess::error::ChessError;
+use chess::notation::pgn::PgnGame;
+use chess::notation::pgn::save_pgn;
+use chess::engine::external::ExternalEngine;
+use chess::engine::search::BackgroundSearch;
+use chess::engine::search::SearchLimits;
+use chess::utils::logs::log;
+
+use crate::cli::Options;
+use crate::cli::Player;
+use crate::cli::Players;
+
+
+// Open the game window on the given position, or on the first of the given games; the process exits when the window is closed
+pub fn run(chess_board: ChessBoard, loaded_games: Vec<PgnGame>, external_engine: Option<ExternalEngine>, options: &Options) {
+    // ggez always looks in resources/ next to the binary; under cargo, also look in chess/resources/
+    let resource_dir = options.resource_dir.clone().or_else(|| {
+        std::env::var_os("CARGO_MANIFEST_DIR").map(|manifest_dir| std::path::PathBuf::from(manifest_dir).join("resources"))
+    });
+
+    // create context and event loop
+    let mut context_builder = ContextBuilder::new("chess", "Rue Starsja")
+        .window_mode(
+            conf::WindowMode::default()
+                .fullscreen_type(conf::FullscreenType::Windowed)
+                .resizable(true)
+        )
+        .window_setup(
+            conf::WindowSetup::default()
+                .title("Chess")
+                .icon("/sprites/black_knight.png")
+        );
+    if let Some(resource_dir) = resource_dir {
+        context_builder = context_builder.add_resource_path(resource_dir);
+    }
+    let ctx_and_event_loop_result = context_builder.build();
+    let (mut ctx, event_loop) = match ctx_and_event_loop_result {
+        Ok(ctx_and_event_loop) => ctx_and_event_loop,
+        Err(error) => {
+            log(
+                "ERROR",
+                format!(
+                    "ggez::ContextBuilder::build returned Err(...): {}",
+                    error
+                )
+            );
+            eprintln!("Couldn't open the game window: {}", error);
+            std::process::exit(1);
+        }
+    };
+
+    // create game
+    let mut game = match Game::construct(&mut ctx, ChessGame::construct(chess_board), options.players, options.engine_limits, external_engine) {
+        Ok(game) => game,
+        Err(error) => {
+            eprintln!("Couldn't start the game: {}", error);
+            std::process::exit(1);
+        }
+    };
+    game.load_games(loaded_games);
+
+    // run game: note that system will exit from inside this function call
+    event::run(ctx, event_loop, game);
+}
+
+
+// Where the board is drawn on screen and which way up; all conversions between spaces and screen coordinates go through here
+struct BoardLayout {
+    board_x: f32,
+    board_y: f32,
+    board_side_len: f32,
+    is_flipped: bool,
+}
+
+
+impl BoardLayout {
+
+    // the largest square that fits the screen, centered along its longer side
+    pub fn construct(screen_dims: graphics::Rect, is_flipped: bool) -> Self {
+        let board_side_len = if screen_dims.w < screen_dims.h { screen_dims.w } else { screen_dims.h };
+        let margin = (screen_dims.w - screen_dims.h).abs() / 2.0;
+        Self {
+            board_x: if screen_dims.w < screen_dims.h { 0.0 } else { margin },
+            board_y: if screen_dims.w < screen_dims.h { margin } else { 0.0 },
+            board_side_len,
+            is_flipped,
+        }
+    }
+
+    pub fn get_space_side_len(&self) -> f32 {
+        (self.board_side_len - 40.0) / 8.0
+    }
+
+    // the screen rectangle of the space; rank 8 is at the top unless flipped
+    pub fn get_space_rect(&self, square: Square) -> graphics::Rect {
+        let (column, row) = if self.is_flipped { (7 - square.file(), 7 - square.rank()) } else { (square.file(), square.rank()) };
+        graphics::Rect::new(
+            self.board_x + 20.0 + (column as f32 * self.get_space_side_len()),
+            self.board_y + 20.0 + (row as f32 * self.get_space_side_len()),
+            self.get_space_side_len(),
+            self.get_space_side_len()
+        )
+    }
+
+    // the space under the given screen coordinates, if any
+    pub fn get_space_at(&self, x: f32, y: f32) -> Option<Square> {
+        if x < (self.board_x + 20.0)
+        || x >= (self.board_x + self.board_side_len - 20.0)
+        || y < (self.board_y + 20.0)
+        || y >= (self.board_y + self.board_side_len - 20.0) {
+            return None;
+        }
+        let column = (((x - (self.board_x + 20.0)) / self.get_space_side_len()).floor() as u8).min(7);
+        let row = (((y - (self.board_y + 20.0)) / self.get_space_side_len()).floor() as u8).min(7);
+        return if self.is_flipped { Square::construct(7 - row, 7 - column) } else { Square::construct(row, column) };
+    }
+
+}
+
+
+struct Game {
+    chess_game: ChessGame,
+    loaded_games: Vec<PgnGame>,
+    loaded_game_index: usize,
+    screen_dims: graphics::Rect,
+    sprites: HashMap<(Color, PieceType), graphics::Image>,
+    selected_space: Option<Square>,
+    selected_moves: Vec<ChessMove>,
+    pending_promotion: Option<(Square, Square)>,
+    // the space a piece is being dragged from, and whether it was already selected before the drag began
+    dragged_space: Option<(Square, bool)>,
+    mouse_position: (f32, f32),
+    is_flipped: bool,
+    auto_flip: bool,
+    players: Players,
+    engine_limits: SearchLimits,
+    // the engine's search in progress, with the FEN of the position it started from
+    engine_search: Option<(EngineSearch, String)>,
+    // plays the engine's moves instead of the built-in search, if given
+    external_engine: Option<ExternalEngine>,
+}
+
+
+// Where the engine's move is coming from
+enum EngineSearch {
+    BuiltIn(BackgroundSearch),
+    // the search is running in the game's ExternalEngine
+    External,
+}
+
+
+impl Game {
+
+    pub fn construct(ctx: &mut Context, chess_game: ChessGame, players: Players, engine_limits: SearchLimits, external_engine: Option<ExternalEngine>) -> Result<Self, ChessError> {
+        // load images, music, etc.
+        let mut sprites: HashMap<(Color, PieceType), graphics::Image> = HashMap::new();
+        for color in [Color::Black, Color::White] {
+            for piece_type in PieceType::ALL {
+                let sprite_path = format!("/sprites/{}_{}.png", color, piece_type);
+                let sprite = graphics::Image::from_path(ctx, &sprite_path)
+                    .map_err(|error| ChessError::Io(format!("Couldn't open sprite {}: {}", sprite_path, error)))?;
+                sprites.insert((color, piece_type), sprite);
+            }
+        }
+
+        // construct Self
+        log("INFO", format!("Starting from the position {}", chess_game.borrow_board().to_fen()));
+        Ok(Self {
+            chess_game,
+            loaded_games: vec![],
+            loaded_game_index: 0,
+            screen_dims: graphics::Rect::new(
+                0., 0., ctx.gfx.drawable_size().0, ctx.gfx.drawable_size().1
+            ),
+            sprites,
+            selected_space: None,
+            selected_moves: vec![],
+            pending_promotion: None,
+            dragged_space: None,
+            mouse_position: (0.0, 0.0),
+            is_flipped: false,
+            auto_flip: false,
+            players,
+            engine_limits,
+            engine_search: None,
+            external_engine,
+        })
+    }
+
+    fn select_space(&mut self, square: Square) {
+        let chess_board = self.chess_game.borrow_board();
+        let piece = chess_board.borrow_space_contents(square);
+        match piece.is_color(chess_board.get_turn()) {
+            true => {
+                log("INFO", "Selected the clicked space.");
+                self.selected_space = Some(square);
+                self.selected_moves = chess_board.legal_moves_from(square);
+            }
+            false => {
+                log("INFO", "Did not select the clicked space; is not the correct turn.");
+                self.selected_space = None;
+            }
+        }
+    }
+
+    // try to move from start to target; on success, unselect
+    fn try_move(&mut self, start: Square, target: Square, promotion: Option<PieceType>) -> bool {
+        if let Err(error) = self.chess_game.play_move(start, target, promotion) {
+            log("INFO", format!("Rejected move: {}", error));
+            return false;
+        }
+        self.selected_space = None;
+        return true;
+    }
+
+    // replace the current game with games read from a PGN file, starting at the first one
+    pub fn load_games(&mut self, games: Vec<PgnGame>) {
+        if games.is_empty() {
+            return;
+        }
+        self.loaded_games = games;
+        self.show_loaded_game(0);
+    }
+
+    // set up the loaded game at index from its first position, with all of its moves ready to step through
+    fn show_loaded_game(&mut self, index: usize) {
+        let game = &self.loaded_games[index];
+        log(
+            "INFO",
+            format!(
+                "Showing loaded game {} of {}: {} vs {} ({}).",
+                index + 1,
+                self.loaded_games.len(),
+                game.get_tag("White").unwrap_or("?"),
+                game.get_tag("Black").unwrap_or("?"),
+                game.result
+            )
+        );
+        self.loaded_game_index = index;
+        self.chess_game = ChessGame::from_pgn_game(game);
+        self.selected_space = None;
+        self.pending_promotion = None;
+    }
+
+    // take back the last move, keeping it to redo later
+    fn undo_move(&mut self) -> bool {
+        self.selected_space = None;
+        self.pending_promotion = None;
+        if !self.chess_game.undo_move() {
+            return false;
+        }
+        // against the engine, take back its reply too so that it's the player's turn again
+        if self.is_engine_turn() {
+            self.chess_game.undo_move();
+        }
+        return true;
+    }
+
+    // replay the last move that was undone
+    fn redo_move(&mut self) -> bool {
+        self.selected_space = None;
+        self.pending_promotion = None;
+        if !self.chess_game.redo_move() {
+            return false;
+        }
+        if self.is_engine_turn() {
+            self.chess_game.redo_move();
+        }
+        return true;
+    }
+
+    // end the game in a draw, if the player to move can claim one
+    fn claim_draw(&mut self) {
+        if self.is_engine_turn() {
+            log("INFO", "Ignored the draw claim; it is the engine's turn.");
+            return;
+        }
+        if self.chess_game.claim_draw() {
+            self.selected_space = None;
+            self.pending_promotion = None;
+        } else {
+            log("INFO", "There is no draw to claim.");
+        }
+    }
+
+    fn is_engine_turn(&self) -> bool {
+        self.players.get(self.chess_game.borrow_board().get_turn()) == Player::Engine
+    }
+
+    // hand the side over to the engine, or back to a human
+    fn toggle_player(&mut self, color: Color) {
+        let player = match self.players.get(color) {
+            Player::Human => Player::Engine,
+            Player::Engine => Player::Human,
+        };
+        self.players.set(color, player);
+        self.selected_space = None;
+        self.pending_promotion = None;
+        log("INFO", format!("The {} now plays {}.", player, color));
+    }
+
+    // start the engine thinking on its turn, and play its move once it has one; the window keeps running meanwhile
+    fn update_engine(&mut self) {
+        let fen = self.chess_game.borrow_board().to_fen();
+        if let Some((engine_search, search_fen)) = self.engine_search.take() {
+            // the position changed under the search (e.g. by undoing), or the side went back to a human
+            if search_fen != fen || !self.is_engine_turn() {
+                log("INFO", "Cancelled the engine's search.");
+                match engine_search {
+                    EngineSearch::BuiltIn(background_search) => background_search.cancel(),
+                    EngineSearch::External => if let Some(external_engine) = &mut self.external_engine {
+                        external_engine.cancel();
+                    },
+                }
+            } else {
+                let best_move = match engine_search {
+                    EngineSearch::BuiltIn(background_search) if !background_search.is_finished() => {
+                        self.engine_search = Some((EngineSearch::BuiltIn(background_search), search_fen));
+                        return;
+                    },
+                    EngineSearch::BuiltIn(background_search) => Ok(background_search.finish()),
+                    EngineSearch::External => match &mut self.external_engine {
+                        Some(external_engine) => external_engine.receive_best_move(Duration::ZERO),
+                        None => Ok(None),
+                    },
+                };
+                match best_move {
+                    Ok(Some(chess_move)) => {
+                        log("INFO", "The engine is making its move.");
+                        self.try_move(chess_move.start, chess_move.target, chess_move.get_promotion());
+                    },
+                    // the external engine is still thinking
+                    Ok(None) => self.engine_search = Some((EngineSearch::External, search_fen)),
+                    Err(error) => self.hand_back_engine_turn(error),
+                }
+                return;
+            }
+        }
+        if self.is_engine_turn() && !self.chess_game.get_outcome().is_over() {
+            log("INFO", "The engine is thinking.");
+            let engine_search = match &mut self.external_engine {
+                None => EngineSearch::BuiltIn(BackgroundSearch::start(self.chess_game.borrow_board().clone(), self.engine_limits)),
+                Some(external_engine) => {
+                    let started = external_engine.go(
+                        self.chess_game.borrow_start_board(),
+                        &self.chess_game.get_moves(),
+                        self.engine_limits
+                    );
+                    if let Err(error) = started {
+                        self.hand_back_engine_turn(error);
+                        return;
+                    }
+                    EngineSearch::External
+                }
+            };
+            self.engine_search = Some((engine_search, fen));
+        }
+    }
+
+    // when the engine fails, a human takes over its side instead of the engine being asked again every frame
+    fn hand_back_engine_turn(&mut self, error: ChessError) {
+        log("ERROR", format!("The engine couldn't move: {}", error));
+        let turn = self.chess_game.borrow_board().get_turn();
+        self.players.set(turn, Player::Human);
+        log("INFO", format!("The {} now plays {}.", Player::Human, turn));
+    }
+
+    // save the game so far to a timestamped PGN file
+    fn save_game(&self) {
+        match save_pgn(&self.chess_game.to_pgn()) {
+            Ok(path) => log("INFO", format!("Saved the game to {}.", path.display())),
+            Err(error) => log("ERROR", format!("Couldn't save the game: {}", error)),
+        }
+    }
+
+    // the promotion picker is drawn in the target file, from the target square towards the middle of the board
+    fn get_promotion_picker_space(target: Square, index: usize) -> Option<Square> {
+        target.offset(if target.rank() == RANK_8 { index as i8 } else { -(index as i8) }, 0)
+    }
+
+    fn get_promotion_choice(target: Square, square: Square) -> Option<PieceType> {
+        for (index, piece_type) in PROMOTION_TYPES.iter().enumerate() {
+            if Self::get_promotion_picker_space(target, index) == Some(square) {
+                return Some(*piece_type);
+            }
+        }
+        None
+    }
+
+    // the board is drawn upside down when flipped by hand, or for black's turn when auto-flipping
+    fn get_layout(&self) -> BoardLayout {
+        let is_flipped = self.is_flipped != (self.auto_flip && self.chess_game.borrow_board().get_turn() == Color::Black);
+        BoardLayout::construct(self.screen_dims, is_flipped)
+    }
+
+    // move the piece at start to target by click or drop; a legal promotion waits for the player to pick a piece
+    fn try_drop(&mut self, start: Square, target: Square) -> bool {
+        if self.chess_game.borrow_board().is_promotion(start, target)
+        && self.chess_game.borrow_board().is_legal_move(start, target) {
+            log("INFO", "The move is a promotion; waiting for the user to choose a piece.");
+            self.pending_promotion = Some((start, target));
+            return true;
+        }
+        if self.try_move(start, target, None) {
+            log("INFO", "Moved the piece from the selected space to the target space.");
+            return true;
+        }
+        log("INFO", "Could not move the piece from the selected space to the target space.");
+        return false;
+    }
+
+    fn make_board_meshes(&self, ctx: &mut Context, layout: &BoardLayout) -> Result<Vec<graphics::Mesh>, ggez::GameError> {
+
+        let mut meshes: Vec<graphics::Mesh> = vec![];
+        
+        // make frame mesh
+        meshes.push(
+            graphics::Mesh::new_rectangle(
+                ctx,
+                graphics::DrawMode::fill(),
+                graphics::Rect::new(
+                    layout.board_x,
+                    layout.board_y,
+                    layout.board_side_len,
+                    layout.board_side_len
+                ),
+                graphics::Color::from_rgb(80, 60, 20)
+            )?
+        );
+
+        // make background mesh (dark squares)
+        meshes.push(
+            graphics::Mesh::new_rectangle(
+                ctx,
+                graphics::DrawMode::fill(),
+                graphics::Rect::new(
+                    layout.board_x + 20.0,
+                    layout.board_y + 20.0,
+                    layout.board_side_len - 40.0,
+                    layout.board_side_len - 40.0
+                ),
+                graphics::Color::from_rgb(140, 110, 65)
+            )?
+        );
+
+        // make light square meshes
+        for square in Square::all() {
+            if (square.rank() + square.file()) % 2 == 0 {
+                meshes.push(
+                    graphics::Mesh::new_rectangle(
+                        ctx,
+                        graphics::DrawMode::fill(),
+                        layout.get_space_rect(square),
+                        graphics::Color::from_rgb(200, 180, 160)
+                    )?
+                );
+            }
+        }
+
+        // return
+        return Ok(meshes);
+    }
+
+}
+
+
+impl event::EventHandler for Game {
+
+    // any frame-by-frame actions not involved in rendering
+    fn update(&mut self, _ctx: &mut Context) -> GameResult {
+        self.update_engine();
+        Ok(())
+    }
+
+    // render each frame
+    fn draw(&mut self, ctx: &mut Context) -> GameResult {
+        // prep canvas
+        let mut canvas = graphics::Canvas::from_frame(ctx, graphics::Color::from_rgb(50, 45, 40));
+        canvas.set_screen_coordinates(self.screen_dims);
+
+        // some useful values
+        let layout = self.get_layout();
+        let board_x = layout.board_x;
+        let board_y = layout.board_y;
+        let board_side_len = layout.board_side_len;
+        let space_side_len = layout.get_space_side_len();
+
+        // draw board
+        let board_meshes = self.make_board_meshes(ctx, &layout)?;
+        for mesh in board_meshes {
+            canvas.draw(&mesh, graphics::DrawParam::default());
+        }
+
+        // draw selected space marker
+        match self.selected_space {
+            None => (),
+            Some(selected_space) => {
+                let selected_space_mesh = graphics::Mesh::new_rectangle(
+                    ctx,
+                    graphics::DrawMode::stroke(5.0),
+                    layout.get_space_rect(selected_space),
+                    graphics::Color::from_rgb(100, 200, 100)
+                )?;
+                canvas.draw(
+                    &selected_space_mesh,
+                    graphics::DrawParam::default()
+                );
+
+                // draw the selected piece's legal moves: a dot on empty spaces, a ring around captures
+                let mut marked_spaces: Vec<Square> = vec![];
+                for chess_move in &self.selected_moves {
+                    let target = chess_move.target;
+                    // promotions give one move per piece choice, but only need one marker
+                    if marked_spaces.contains(&target) {
+                        continue;
+                    }
+                    marked_spaces.push(target);
+                    let center = layout.get_space_rect(target).center();
+                    let is_capture = chess_move.kind == MoveKind::EnPassant
+                        || !self.chess_game.borrow_board().borrow_space_contents(target).is_empty();
+                    let target_mesh = if is_capture {
+                        graphics::Mesh::new_circle(
+                            ctx,
+                            graphics::DrawMode::stroke(space_side_len / 12.0),
+                            center,
+                            space_side_len * 0.45,
+                            0.5,
+                            graphics::Color::from_rgba(200, 60, 40, 170)
+                        )?
+                    } else {
+                        graphics::Mesh::new_circle(
+                            ctx,
+                            graphics::DrawMode::fill(),
+                            center,
+                            space_side_len / 6.0,
+                            0.5,
+                            graphics::Color::from_rgba(60, 120, 60, 170)
+                        )?
+                    };
+                    canvas.draw(&target_mesh, graphics::DrawParam::default());
+                }
+            }
+        }
+
+        // draw pieces
+        for square in Square::all() {
+            let piece = self.chess_game.borrow_board().borrow_space_contents(square);
+            let is_dragged = self.dragged_space.is_some_and(|(space, _)| space == square);
+            if let (Some(color), Some(piece_type)) = (piece.get_color(), piece.get_type()) && !is_dragged {
+                let sprite = &self.sprites[&(color, piece_type)];
+                canvas.draw(
+                    sprite,
+                    graphics::DrawParam::default()
+                        .dest_rect(graphics::Rect::new(
+                            layout.get_space_rect(square).x,
+                            layout.get_space_rect(square).y,
+                            space_side_len / sprite.width() as f32,
+                            space_side_len / sprite.height() as f32
+                        ))
+                );
+            }
+        }
+
+        // draw the dragged piece under the cursor
+        if let Some((square, _)) = self.dragged_space {
+            let piece = self.chess_game.borrow_board().borrow_space_contents(square);
+            if let (Some(color), Some(piece_type)) = (piece.get_color(), piece.get_type()) {
+                let sprite = &self.sprites[&(color, piece_type)];
+                canvas.draw(
+                sprite,
+                graphics::DrawParam::default()
+                    .dest_rect(graphics::Rect::new(
+                        self.mouse_position.0 - space_side_len / 2.0,
+                        self.mouse_position.1 - space_side_len / 2.0,
+                        space_side_len / sprite.width() as f32,
+                        space_side_len / sprite.height() as f32
+                    ))
+                );
+            }
+        }
+
+        // draw promotion picker
+        if let Some((_, target)) = self.pending_promotion {
+            let color = self.chess_game.borrow_board().get_turn();
+            for (index, piece_type) in PROMOTION_TYPES.iter().enumerate() {
+                let option_rect = match Self::get_promotion_picker_space(target, index) {
+                    Some(option_space) => layout.get_space_rect(option_space),
+                    None => continue,
+                };
+                let option_mesh = graphics::Mesh::new_rectangle(
+                    ctx,
+                    graphics::DrawMode::fill(),
+                    option_rect,
+                    graphics::Color::from_rgb(235, 225, 210)
+                )?;
+                canvas.draw(&option_mesh, graphics::DrawParam::default());
+                let option_border_mesh = graphics::Mesh::new_rectangle(
+                    ctx,
+                    graphics::DrawMode::stroke(2.0),
+                    option_rect,
+                    graphics::Color::from_rgb(80, 60, 20)
+                )?;
+                canvas.draw(&option_border_mesh, graphics::DrawParam::default());
+                let sprite = &self.sprites[&(color, *piece_type)];
+                canvas.draw(
+                    sprite,
+                    graphics::DrawParam::default()
+                        .dest_rect(graphics::Rect::new(
+                            option_rect.x,
+                            option_rect.y,
+                            option_rect.w / sprite.width() as f32,
+                            option_rect.h / sprite.height() as f32
+                        ))
+                );
+            }
+        }
+
+        // draw who plays each side in the top frame, along with any draw the player to move can claim
+        let claimable_draw = match self.chess_game.get_claimable_draw() {
+            Some(draw) if !self.is_engine_turn() => format!("   {} can be claimed (D)", draw),
+            _ => String::new(),
+        };
+        let mut players_text = graphics::Text::new(format!(
+            "White: {} (W)   Black: {} (B){}{}",
+            self.players.white,
+            self.players.black,
+            if self.engine_search.is_some() { "   The engine is thinking..." } else { "" },
+            claimable_draw
+        ));
+        players_text.set_scale(14.0);
+        canvas.draw(
+            &players_text,
+            graphics::DrawParam::default()
+                .dest([board_x + 20.0, board_y + 3.0])
+                .color(graphics::Color::from_rgb(240, 230, 210))
+        );
+
+        // draw game outcome banner
+        let outcome = self.chess_game.get_outcome();
+        if outcome.is_over() {
+            let mut outcome_text = graphics::Text::new(outcome.to_string());
+            outcome_text.set_scale(board_side_len / 16.0);
+            let outcome_text_dims = outcome_text.measure(ctx)?;
+            let banner_mesh = graphics::Mesh::new_rectangle(
+                ctx,
+                graphics::DrawMode::fill(),
+                graphics::Rect::new(
+                    board_x + 20.0,
+                    board_y + (board_side_len - outcome_text_dims.y) / 2.0 - 20.0,
+                    board_side_len - 40.0,
+                    outcome_text_dims.y + 40.0
+                ),
+                graphics::Color::from_rgba(50, 45, 40, 220)
+            )?;
+            canvas.draw(&banner_mesh, graphics::DrawParam::default());
+            canvas.draw(
+                &outcome_text,
+                graphics::DrawParam::default()
+                    .dest([
+                        board_x + (board_side_len - outcome_text_dims.x) / 2.0,
+                        board_y + (board_side_len - outcome_text_dims.y) / 2.0
+                    ])
+                    .color(graphics::Color::from_rgb(240, 230, 210))
+            );
+        }
+
+        // finish canvas
+        canvas.finish(ctx)
+    }
+
+    // react to a click (or the start of a drag)
+    fn mouse_button_down_event(
+            &mut self,
+            _ctx: &mut Context,
+            _button: event::MouseButton,
+            x: f32,
+            y: f32,
+        ) -> GameResult {
+        // log info
+        log(
+            "INFO",
+            format!(
+                "User clicked at coordinates (x {}, y {}).",
+                x, y
+            )
+        );
+        self.mouse_position = (x, y);
+
+        // ignore clicks once the game is over
+        if self.chess_game.get_outcome().is_over() {
+            log("INFO", format!("Ignored the click; the game is over ({}).", self.chess_game.get_outcome()));
+            return Ok(());
+        }
+
+        // the engine moves for its own side
+        if self.is_engine_turn() {
+            log("INFO", "Ignored the click; it is the engine's turn.");
+            return Ok(());
+        }
+
+        // clicked outside of the spaces
+        let square = match self.get_layout().get_space_at(x, y) {
+            Some(space) => space,
+            None => {
+                log(
+                    "INFO",
+                    "User did not click on a space."
+                );
+                return Ok(());
+            }
+        };
+        log(
+            "INFO",
+            format!("User clicked on the space {}.", square)
+        );
+
+        // a promotion is waiting on the choice of piece
+        if let Some((start, target)) = self.pending_promotion {
+            self.pending_promotion = None;
+            match Self::get_promotion_choice(target, square) {
+                Some(piece_type) => {
+                    log("INFO", format!("User chose to promote to a {}.", piece_type));
+                    self.try_move(start, target, Some(piece_type));
+                },
+                None => {
+                    log("INFO", "User clicked outside of the promotion picker; cancelled the promotion.");
+                    self.selected_space = None;
+                }
+            }
+            return Ok(());
+        }
+        match self.selected_space {
+            // no previously selected space
+            None => {
+                log("INFO", "Didn't have a space selected previously.");
+                // select the clicked space
+                self.select_space(square);
+            },
+            // clicked on the previously selected space; it is unselected when the mouse is released without dragging
+            Some(space) if space == square => {
+                log("INFO", "User clicked the previously selected space.");
+                self.dragged_space = Some((space, true));
+                return Ok(());
+            },
+            // clicked on another space
+            Some(space) => {
+                log(
+                    "INFO",
+                    format!("Previously had {} selected.", space)
+                );
+                log("INFO", "Trying to move piece from the previously selected space to the newly clicked space.");
+                if self.try_drop(space, square) {
+                    return Ok(());
+                }
+                // ...otherwise select the clicked space
+                self.select_space(square);
+            }
+        }
+        // a newly selected piece can be dragged from here
+        if self.selected_space == Some(square) {
+            self.dragged_space = Some((square, false));
+        }
+        Ok(())
+    }
+
+    // follow the cursor with any dragged piece
+    fn mouse_motion_event(&mut self, _ctx: &mut Context, x: f32, y: f32, _dx: f32, _dy: f32) -> GameResult {
+        self.mouse_position = (x, y);
+        Ok(())
+    }
+
+    // react to the end of a click or drag
+    fn mouse_button_up_event(
+            &mut self,
+            _ctx: &mut Context,
+            _button: event::MouseButton,
+            x: f32,
+            y: f32,
+        ) -> GameResult {
+        self.mouse_position = (x, y);
+        let (start, was_selected) = match self.dragged_space.take() {
+            Some(dragged_space) => dragged_space,
+            None => return Ok(()),
+        };
+        match self.get_layout().get_space_at(x, y) {
+            // released where the press began: a plain click
+            Some(space) if space == start => {
+                if was_selected {
+                    // unselect
+                    self.selected_space = None;
+                    log("INFO", "Unselected the previously selected space.");
+                }
+            },
+            // dropped on another space
+            Some(target) => {
+                log(
+                    "INFO",
+                    format!("User dropped the piece on {}.", target)
+                );
+                if !self.try_drop(start, target) {
+                    log("INFO", "Snapped the dragged piece back to its space.");
+                }
+            },
+            // dropped off the board
+            None => log("INFO", "User dropped the piece off the board; snapped it back to its space."),
+        }
+        Ok(())
+    }
+
+    // react to a key press
+    fn key_down_event(&mut self, ctx: &mut Context, input: KeyInput, _repeated: bool) -> GameResult {
+        match input.keycode {
+            // keep ggez's default of quitting on escape
+            Some(KeyCode::Escape) => ctx.request_quit(),
+            // ctrl+s saves the game
+            Some(KeyCode::S) if input.mods.contains(KeyMods::CTRL) => {
+                log("INFO", "User pressed ctrl+s.");
+                self.save_game();
+            },
+            // ctrl+z undoes a move, ctrl+y or ctrl+shift+z redoes it
+            Some(KeyCode::Z) if input.mods.contains(KeyMods::CTRL | KeyMods::SHIFT) => {
+                self.redo_move();
+            },
+            Some(KeyCode::Z) if input.mods.contains(KeyMods::CTRL) => {
+                self.undo_move();
+            },
+            Some(KeyCode::Y) if input.mods.contains(KeyMods::CTRL) => {
+                self.redo_move();
+            },
+            // arrow keys step through the moves, home and end jump to either end
+            Some(KeyCode::Left) => {
+                self.undo_move();
+            },
+            Some(KeyCode::Right) => {
+                self.redo_move();
+            },
+            Some(KeyCode::Home) => while self.undo_move() {},
+            Some(KeyCode::End) => while self.redo_move() {},
+            // f flips the board, a toggles flipping it to the side to move
+            Some(KeyCode::F) => {
+                self.is_flipped = !self.is_flipped;
+                log("INFO", format!("Flipped the board (flipped: {}).", self.is_flipped));
+            },
+            // w and b hand white or black to the engine, or back to a human
+            Some(KeyCode::W) => self.toggle_player(Color::White),
+            Some(KeyCode::B) => self.toggle_player(Color::Black),
+            Some(KeyCode::A) => {
+                self.auto_flip = !self.auto_flip;
+                log("INFO", format!("Toggled flipping the board to the side to move (auto-flip: {}).", self.auto_flip));
+            },
+            // d claims a draw by repetition or the fifty-move rule
+            Some(KeyCode::D) => self.claim_draw(),
+            // page up and page down switch between the games of a loaded PGN file
+            Some(KeyCode::PageUp) if self.loaded_game_index > 0 => {
+                self.show_loaded_game(self.loaded_game_index - 1);
+            },
+            Some(KeyCode::PageDown) if self.loaded_game_index + 1 < self.loaded_games.len() => {
+                self.show_loaded_game(self.loaded_game_index + 1);
+            },
+            _ => (),
+        }
+        Ok(())
+    }
+
+    // react to the window being resized
+    fn resize_event(&mut self, _ctx: &mut Context, width: f32, height: f32) -> GameResult {
+        // update the recorded screen dimensions
+        self.screen_dims = graphics::Rect::new(
+            0., 0., width, height
+        );
+        Ok(())
+    }
+
+    // react to clicking the "X" or pressing the escape key
+    fn quit_event(&mut self, _ctx: &mut Context) -> Result<bool, ggez::GameError> {
+        log("INFO", "The program will exit now.");
+        // Ok(true) = keep running, Ok(false) = exit
+        Ok(false)
+    }
+
+}
@@ -0,0 +1,10 @@
+#![allow(clippy::needless_return)]
+
+// crate
+
+pub mod components;
+pub mod engine;
+pub mod error;
+pub mod notation;
+pub mod rules;
+pub mod utils;
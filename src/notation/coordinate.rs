@@ -0,0 +1,75 @@
+// crate::notation::coordinate
+
+use crate::components::chess_board::ChessBoard;
+use crate::components::chess_board::PROMOTION_TYPES;
+use crate::components::chess_move::ChessMove;
+use crate::components::piece_type::PieceType;
+use crate::components::square::Square;
+use crate::error::ChessError;
+
+
+// Writes a move as its start and target spaces, plus any promotion piece, e.g. "e2e4", "e1g1" or "e7e8q"
+pub fn to_coordinate(chess_move: &ChessMove) -> String {
+    let mut coordinate = format!("{}{}", chess_move.start, chess_move.target);
+    if let Some(piece_type) = chess_move.get_promotion() {
+        coordinate.push(piece_type.get_letter().to_ascii_lowercase());
+    }
+    coordinate
+}
+
+// Finds the legal move that a move in coordinate notation describes; a dash between the spaces is allowed (e.g. "e2-e4")
+pub fn parse_coordinate(chess_board: &ChessBoard, coordinate: &str) -> Result<ChessMove, ChessError> {
+    let chars: Vec<char> = coordinate.trim().chars().filter(|label| *label != '-').collect();
+    if chars.len() != 4 && chars.len() != 5 {
+        return Err(ChessError::Parse(format!("\"{}\" is not a valid coordinate move.", coordinate)));
+    }
+    let start = Square::from_labels(chars[0], chars[1])
+        .map_err(|error| error.with_context(&format!("\"{}\" has an invalid start space", coordinate)))?;
+    let target = Square::from_labels(chars[2], chars[3])
+        .map_err(|error| error.with_context(&format!("\"{}\" has an invalid target space", coordinate)))?;
+    let promotion = match chars.get(4) {
+        None => None,
+        Some(letter) => Some(
+            PieceType::from_letter(letter.to_ascii_uppercase())
+                .filter(|piece_type| PROMOTION_TYPES.contains(piece_type))
+                .ok_or(ChessError::Parse(format!("\"{}\" has the invalid promotion piece '{}'.", coordinate, letter)))?
+        ),
+    };
+
+    let chess_move = chess_board.legal_moves_from(start)
+        .into_iter()
+        .find(|chess_move| {
+            chess_move.target == target && chess_move.get_promotion() == promotion
+        });
+    match chess_move {
+        Some(chess_move) => Ok(chess_move),
+        None if promotion.is_none() && chess_board.is_promotion(start, target) => {
+            Err(ChessError::IllegalMove(format!("\"{}\" needs a piece to promote to, e.g. \"{}q\".", coordinate, coordinate)))
+        },
+        None => Err(ChessError::IllegalMove(format!("\"{}\" is not a legal move here.", coordinate))),
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use crate::components::chess_move::MoveKind;
+
+    #[test]
+    fn reads_and_writes_coordinate_moves() {
+        let chess_board = ChessBoard::from_fen("r3k2r/1P6/8/8/8/8/8/4K3 w kq - 0 1").unwrap();
+        let castle = ChessMove::construct(Square::E8, Square::G8, MoveKind::KingsideCastle);
+        let promotion = ChessMove::construct(Square::B7, Square::B8, MoveKind::Promotion(PieceType::Knight));
+        assert_eq!(to_coordinate(&promotion), "b7b8n");
+        assert_eq!(parse_coordinate(&chess_board, "b7b8n"), Ok(promotion));
+        assert_eq!(parse_coordinate(&chess_board, "e1-d2").map(|chess_move| to_coordinate(&chess_move)), Ok(String::from("e1d2")));
+        assert!(matches!(parse_coordinate(&chess_board, "b7b8"), Err(ChessError::IllegalMove(_))));
+        assert!(matches!(parse_coordinate(&chess_board, "e1e3"), Err(ChessError::IllegalMove(_))));
+        assert!(matches!(parse_coordinate(&chess_board, "e1i2"), Err(ChessError::Parse(_))));
+        assert!(matches!(parse_coordinate(&chess_board, "b7b8k"), Err(ChessError::Parse(_))));
+        assert_eq!(to_coordinate(&castle), "e8g8");
+    }
+
+}
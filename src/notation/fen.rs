@@ -0,0 +1,273 @@
+// crate::notation::fen
+
+use crate::components::chess_board::ChessBoard;
+use crate::components::chess_piece::ChessPiece;
+use crate::components::color::Color;
+use crate::components::square::FILE_H;
+use crate::components::square::Square;
+use crate::error::ChessError;
+
+
+// (castling right, king space, rook space)
+const CASTLING_RIGHTS: [(char, Square, Square); 4] = [
+    ('K', Square::E1, Square::H1),
+    ('Q', Square::E1, Square::A1),
+    ('k', Square::E8, Square::H8),
+    ('q', Square::E8, Square::A8),
+];
+
+
+impl ChessBoard {
+
+    // Reads a position in Forsyth-Edwards Notation; the halfmove clock and fullmove number may be left off
+    pub fn from_fen(fen: &str) -> Result<Self, ChessError> {
+        let fields: Vec<&str> = fen.split_whitespace().collect();
+        if fields.len() < 4 || fields.len() > 6 {
+            return Err(ChessError::Parse(format!("Expected 4 to 6 fields in the FEN \"{}\", found {}.", fen, fields.len())));
+        }
+
+        // Piece placement, from rank 8 down to rank 1
+        let mut contents: [[ChessPiece; 8]; 8] = Default::default();
+        let rows: Vec<&str> = fields[0].split('/').collect();
+        if rows.len() != 8 {
+            return Err(ChessError::Parse(format!("Expected 8 ranks in the piece placement \"{}\", found {}.", fields[0], rows.len())));
+        }
+        for (rank, row) in rows.iter().enumerate() {
+            let mut file: usize = 0;
+            for label in row.chars() {
+                if let Some(empty_count) = label.to_digit(10) {
+                    if !(1..=8).contains(&empty_count) {
+                        return Err(ChessError::Parse(format!("Invalid empty space count '{}' in the piece placement.", label)));
+                    }
+                    file += empty_count as usize;
+                } else {
+                    if file >= 8 {
+                        return Err(ChessError::Parse(format!("Rank {} of the piece placement has more than 8 files.", 8 - rank)));
+                    }
+                    contents[rank][file] = ChessPiece::from_letter(label)?;
+                    file += 1;
+                }
+            }
+            if file != 8 {
+                return Err(ChessError::Parse(format!("Rank {} of the piece placement has {} files instead of 8.", 8 - rank, file)));
+            }
+        }
+        for color in [Color::White, Color::Black] {
+            let king_count = contents.iter().flatten()
+                .filter(|piece| piece.is_king() && piece.is_color(color))
+                .count();
+            if king_count != 1 {
+                return Err(ChessError::Parse(format!("Expected exactly one {} king, found {}.", color, king_count)));
+            }
+        }
+        if contents[0].iter().chain(contents[7].iter()).any(|piece| piece.is_pawn()) {
+            return Err(ChessError::Parse(String::from("Pawns cannot be on the first or last rank.")));
+        }
+
+        // Side to move
+        let turn = match fields[1] {
+            "w" => Color::White,
+            "b" => Color::Black,
+            _ => return Err(ChessError::Parse(format!("Invalid side to move \"{}\" (expected \"w\" or \"b\").", fields[1]))),
+        };
+
+        // Castling rights; every king and rook that can't castle counts as having moved
+        let mut unmoved_spaces: Vec<Square> = vec![];
+        if fields[2] != "-" {
+            for right in fields[2].chars() {
+                let (_, king_space, rook_space) = match CASTLING_RIGHTS.iter().find(|(label, _, _)| *label == right) {
+                    Some(castling_right) => *castling_right,
+                    None => return Err(ChessError::Parse(format!("Invalid castling right '{}'.", right))),
+                };
+                let king = &contents[king_space.rank() as usize][king_space.file() as usize];
+                let rook = &contents[rook_space.rank() as usize][rook_space.file() as usize];
+                if !(king.is_king() && rook.is_rook() && king.get_color() == rook.get_color()
+                    && king.is_black() == right.is_lowercase()) {
+                    return Err(ChessError::Parse(format!("Castling right '{}' requires the king and rook on their starting spaces.", right)));
+                }
+                unmoved_spaces.push(king_space);
+                unmoved_spaces.push(rook_space);
+            }
+        }
+        for (rank, row) in contents.iter_mut().enumerate() {
+            for (file, piece) in row.iter_mut().enumerate() {
+                if (piece.is_king() || piece.is_rook())
+                && Square::construct(rank as u8, file as u8).is_none_or(|square| !unmoved_spaces.contains(&square)) {
+                    piece.mark_moved();
+                }
+            }
+        }
+
+        // En passant target, stored as the double pawn move that allowed it
+        let last_move = if fields[3] == "-" {
+            None
+        } else {
+            let target = Square::from_label(fields[3])?;
+            let (start_rank, end_rank) = if turn == Color::Black { (6, 4) } else { (1, 3) };
+            if target.rank() != (start_rank + end_rank) / 2 {
+                return Err(ChessError::Parse(format!("The en passant target \"{}\" is not on the expected rank.", fields[3])));
+            }
+            let pawn = &contents[end_rank as usize][target.file() as usize];
+            if !pawn.is_pawn() || pawn.is_color(turn) {
+                return Err(ChessError::Parse(format!("The en passant target \"{}\" is not behind a pawn that just moved.", fields[3])));
+            }
+            Square::construct(start_rank, target.file()).zip(Square::construct(end_rank, target.file()))
+        };
+
+        // Move counters
+        let halfmove_clock = match fields.get(4) {
+            None => 0,
+            Some(field) => field.parse::<u32>().map_err(|_| ChessError::Parse(format!("Invalid halfmove clock \"{}\".", field)))?,
+        };
+        let fullmove_number = match fields.get(5) {
+            None => 1,
+            Some(field) => match field.parse::<u32>() {
+                Ok(number) if number >= 1 => number,
+                _ => return Err(ChessError::Parse(format!("Invalid fullmove number \"{}\".", field))),
+            },
+        };
+
+        Ok(Self::construct(contents, turn, last_move, halfmove_clock, fullmove_number))
+    }
+
+    pub fn to_fen(&self) -> String {
+        // Piece placement, from rank 8 down to rank 1
+        let mut rows: Vec<String> = vec![];
+        let mut row = String::new();
+        let mut empty_count = 0;
+        for square in Square::all() {
+            let piece = self.borrow_space_contents(square);
+            if piece.is_empty() {
+                empty_count += 1;
+            } else {
+                if empty_count > 0 {
+                    row.push_str(&empty_count.to_string());
+                    empty_count = 0;
+                }
+                row.push_str(&piece.to_string());
+            }
+            if square.file() == FILE_H {
+                if empty_count > 0 {
+                    row.push_str(&empty_count.to_string());
+                    empty_count = 0;
+                }
+                rows.push(std::mem::take(&mut row));
+            }
+        }
+
+        // Castling rights
+        let mut castling_rights = String::new();
+        for ((label, _, _), has_right) in CASTLING_RIGHTS.iter().zip(self.get_castling_rights()) {
+            if has_right {
+                castling_rights.push(*label);
+            }
+        }
+        if castling_rights.is_empty() {
+            castling_rights.push('-');
+        }
+
+        // En passant target, behind a pawn that just moved two spaces
+        let en_passant = match *self.get_last_move() {
+            Some((start, target))
+            if start.file() == target.file()
+            && start.rank().abs_diff(target.rank()) == 2
+            && self.borrow_space_contents(target).is_pawn() => {
+                format!(
+                    "{}{}",
+                    Square::get_file_label(target.file()),
+                    Square::get_rank_label((start.rank() + target.rank()) / 2)
+                )
+            },
+            _ => String::from("-"),
+        };
+
+        format!(
+            "{} {} {} {} {} {}",
+            rows.join("/"),
+            if self.get_turn() == Color::Black { "b" } else { "w" },
+            castling_rights,
+            en_passant,
+            self.get_halfmove_clock(),
+            self.get_fullmove_number()
+        )
+    }
+
+    // Which of white's kingside, white's queenside, black's kingside and black's queenside castling are still possible
+    pub fn get_castling_rights(&self) -> [bool; 4] {
+        CASTLING_RIGHTS.map(|(label, king_space, rook_space)| {
+            let king = self.borrow_space_contents(king_space);
+            let rook = self.borrow_space_contents(rook_space);
+            king.is_king() && rook.is_rook() && !king.has_moved() && !rook.has_moved()
+            && king.get_color() == rook.get_color() && king.is_black() == label.is_lowercase()
+        })
+    }
+
+}
+
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    const START_FEN: &str = "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1";
+
+    #[test]
+    fn start_fen_matches_default_board() {
+        assert_eq!(ChessBoard::default().to_fen(), START_FEN);
+        assert_eq!(ChessBoard::from_fen(START_FEN).unwrap().to_fen(), START_FEN);
+    }
+
+    #[test]
+    fn round_trips_castling_rights_en_passant_and_counters() {
+        for fen in [
+            "r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1",
+            "rnbqkbnr/ppp1p1pp/8/3pPp2/8/8/PPPP1PPP/RNBQKBNR w KQkq f6 0 3",
+            "8/2p5/3p4/KP5r/1R3p1k/8/4P1P1/8 w - - 0 1",
+            "r3k2r/Pppp1ppp/1b3nbN/nP6/BBP1P3/q4N2/Pp1P2PP/R2Q1RK1 w kq - 0 1",
+            "rnbq1k1r/pp1Pbppp/2p5/8/2B5/8/PPP1NnPP/RNBQK2R w KQ - 1 8",
+            "4k3/8/8/8/3Pp3/8/8/4K3 b - d3 0 12",
+        ] {
+            assert_eq!(ChessBoard::from_fen(fen).unwrap().to_fen(), fen);
+        }
+    }
+
+    #[test]
+    fn defaults_missing_counters() {
+        assert_eq!(
+            ChessBoard::from_fen("4k3/8/8/8/8/8/8/4K3 b - -").unwrap().to_fen(),
+            "4k3/8/8/8/8/8/8/4K3 b - - 0 1"
+        );
+        assert!(ChessBoard::from_fen("4k3/8/8/8/8/8/8/4K3 b -").is_err());
+    }
+
+    #[test]
+    fn tracks_state_as_moves_are_made() {
+        let mut chess_board = ChessBoard::default();
+        assert!(chess_board.move_piece(Square::E2, Square::E4, None).is_ok());
+        assert_eq!(chess_board.to_fen(), "rnbqkbnr/pppppppp/8/8/4P3/8/PPPP1PPP/RNBQKBNR b KQkq e3 0 1");
+        assert!(chess_board.move_piece(Square::G8, Square::F6, None).is_ok());
+        assert!(chess_board.move_piece(Square::E1, Square::E2, None).is_ok());
+        assert_eq!(chess_board.to_fen(), "rnbqkb1r/pppppppp/5n2/8/4P3/8/PPPPKPPP/RNBQ1BNR b kq - 2 2");
+    }
+
+    #[test]
+    fn rejects_invalid_fens() {
+        for fen in [
+            "",
+            "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP w KQkq - 0 1",
+            "rnbqkbnr/pppppppp/9/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1",
+            "rnbqkbnr/ppppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1",
+            "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNX w KQkq - 0 1",
+            "rnbq1bnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQ - 0 1",
+            "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR x KQkq - 0 1",
+            "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBN1 w KQkq - 0 1",
+            "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq e3 0 1",
+            "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - x 1",
+            "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 0",
+        ] {
+            assert!(ChessBoard::from_fen(fen).is_err(), "{}", fen);
+        }
+    }
+
+}
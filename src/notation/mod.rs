@@ -0,0 +1,6 @@
+// crate::notation
+
+pub mod coordinate;
+pub mod fen;
+pub mod pgn;
+pub mod san;
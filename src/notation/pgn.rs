@@ -0,0 +1,365 @@
+// crate::notation::pgn
+
+use chrono::Local;
+use std::fs;
+use std::path::PathBuf;
+
+use crate::components::chess_board::ChessBoard;
+use crate::components::chess_move::ChessMove;
+use crate::components::color::Color;
+use crate::components::game_outcome::GameOutcome;
+use crate::error::ChessError;
+use crate::notation::san::parse_san;
+use crate::notation::san::to_san;
+use crate::utils::logs::with_logs_muted;
+
+const GAMES_DIR: &str = "games";
+const MAX_LINE_LEN: usize = 80;
+const RESULTS: [&str; 4] = ["1-0", "0-1", "1/2-1/2", "*"];
+
+
+// A game read from PGN, with its mainline replayed into legal moves
+pub struct PgnGame {
+    pub tags: Vec<(String, String)>,
+    pub start_board: ChessBoard,
+    pub moves: Vec<ChessMove>,
+    pub result: String,
+}
+
+
+impl PgnGame {
+
+    pub fn get_tag(&self, name: &str) -> Option<&str> {
+        self.tags.iter()
+            .find(|(tag_name, _)| tag_name == name)
+            .map(|(_, value)| value.as_str())
+    }
+
+}
+
+
+// Writes a game as PGN, with the Seven Tag Roster and, for games not from the standard start, its FEN
+pub fn write_pgn(start_board: &ChessBoard, moves: &[ChessMove], outcome: GameOutcome) -> String {
+    let result = get_result(outcome);
+    let mut pgn = String::new();
+
+    // Tag pairs
+    let start_fen = start_board.to_fen();
+    let mut tags: Vec<(&str, String)> = vec![
+        ("Event", String::from("Casual game")),
+        ("Site", String::from("?")),
+        ("Date", format!("{}", Local::now().format("%Y.%m.%d"))),
+        ("Round", String::from("-")),
+        ("White", String::from("?")),
+        ("Black", String::from("?")),
+        ("Result", String::from(result)),
+    ];
+    if start_fen != ChessBoard::default().to_fen() {
+        tags.push(("SetUp", String::from("1")));
+        tags.push(("FEN", start_fen));
+    }
+    for (name, value) in tags {
+        pgn.push_str(&format!("[{} \"{}\"]\n", name, value.replace('\\', "\\\\").replace('"', "\\\"")));
+    }
+    pgn.push('\n');
+
+    // Movetext, replayed from the start to get each move's SAN
+    let mut tokens: Vec<String> = vec![];
+    let mut chess_board = start_board.clone();
+    for (index, chess_move) in moves.iter().enumerate() {
+        if chess_board.get_turn() == Color::White {
+            tokens.push(format!("{}.", chess_board.get_fullmove_number()));
+        } else if index == 0 {
+            tokens.push(format!("{}...", chess_board.get_fullmove_number()));
+        }
+        tokens.push(to_san(&chess_board, chess_move));
+        with_logs_muted(|| chess_board.make_move(chess_move));
+    }
+    tokens.push(String::from(result));
+
+    // Wrap the movetext into lines of at most MAX_LINE_LEN characters
+    let mut line = String::new();
+    for token in tokens {
+        if !line.is_empty() && line.len() + 1 + token.len() > MAX_LINE_LEN {
+            pgn.push_str(&line);
+            pgn.push('\n');
+            line.clear();
+        }
+        if !line.is_empty() {
+            line.push(' ');
+        }
+        line.push_str(&token);
+    }
+    pgn.push_str(&line);
+    pgn.push('\n');
+
+    pgn
+}
+
+// Reads every game in a PGN file, skipping comments and variations, and replays each mainline
+pub fn parse_pgn(pgn: &str) -> Result<Vec<PgnGame>, ChessError> {
+    let mut games: Vec<PgnGame> = vec![];
+    let mut tags: Vec<(String, String)> = vec![];
+    let mut sans: Vec<String> = vec![];
+    let mut is_in_movetext = false;
+    let mut is_line_start = true;
+    let mut chars = pgn.chars().peekable();
+
+    while let Some(label) = chars.next() {
+        let was_line_start = is_line_start;
+        is_line_start = label == '\n';
+        match label {
+            // Tag pair; one after some movetext starts the next game
+            '[' => {
+                if is_in_movetext {
+                    games.push(replay_game(games.len() + 1, tags, sans, String::from("*"))?);
+                    tags = vec![];
+                    sans = vec![];
+                    is_in_movetext = false;
+                }
+                let tag: String = chars.by_ref().take_while(|label| *label != '\n').collect();
+                tags.push(
+                    parse_tag(&tag)
+                        .ok_or(ChessError::Parse(format!("Game {}: malformed tag \"[{}\".", games.len() + 1, tag)))?
+                );
+                is_line_start = true;
+            },
+            // Comments, escaped lines and variations are skipped
+            '{' => {
+                chars.by_ref().find(|label| *label == '}');
+            },
+            ';' => {
+                chars.by_ref().find(|label| *label == '\n');
+                is_line_start = true;
+            },
+            '%' if was_line_start => {
+                chars.by_ref().find(|label| *label == '\n');
+                is_line_start = true;
+            },
+            '(' => {
+                let mut depth = 1;
+                while depth > 0 {
+                    match chars.next() {
+                        Some('(') => depth += 1,
+                        Some(')') => depth -= 1,
+                        Some('{') => {
+                            chars.by_ref().find(|label| *label == '}');
+                        },
+                        Some(_) => (),
+                        None => return Err(ChessError::Parse(format!("Game {}: unclosed variation.", games.len() + 1))),
+                    }
+                }
+            },
+            _ if label.is_whitespace() => (),
+            // Move numbers, moves, annotations and results
+            _ => {
+                let mut token = String::from(label);
+                while let Some(next_label) = chars.peek() {
+                    if next_label.is_whitespace() || "[]{}();".contains(*next_label) {
+                        break;
+                    }
+                    token.push(chars.next().unwrap());
+                }
+                is_in_movetext = true;
+                if RESULTS.contains(&token.as_str()) {
+                    games.push(replay_game(games.len() + 1, tags, sans, token)?);
+                    tags = vec![];
+                    sans = vec![];
+                    is_in_movetext = false;
+                    continue;
+                }
+                if token.starts_with('$') {
+                    continue;
+                }
+                // strip a move number such as "12." or "12...", which may be joined onto the move
+                let san = if token.starts_with(|label: char| label.is_ascii_digit()) && token.contains('.') {
+                    token.trim_start_matches(|label: char| label.is_ascii_digit()).trim_start_matches('.')
+                } else {
+                    token.trim_start_matches('.')
+                };
+                if !san.is_empty() {
+                    sans.push(String::from(san));
+                }
+            },
+        }
+    }
+
+    // A last game without a result
+    if !tags.is_empty() || !sans.is_empty() {
+        games.push(replay_game(games.len() + 1, tags, sans, String::from("*"))?);
+    }
+    Ok(games)
+}
+
+// Reads the inside of a tag pair such as [Event "Casual game"] (without the opening bracket)
+fn parse_tag(tag: &str) -> Option<(String, String)> {
+    let tag = tag.trim().strip_suffix(']')?.trim();
+    let (name, value) = tag.split_once(char::is_whitespace)?;
+    let value = value.trim().strip_prefix('"')?.strip_suffix('"')?;
+    let mut unescaped_value = String::new();
+    let mut value_chars = value.chars();
+    while let Some(label) = value_chars.next() {
+        if label == '\\' {
+            unescaped_value.push(value_chars.next()?);
+        } else {
+            unescaped_value.push(label);
+        }
+    }
+    Some((String::from(name), unescaped_value))
+}
+
+fn replay_game(game_number: usize, tags: Vec<(String, String)>, sans: Vec<String>, result: String) -> Result<PgnGame, ChessError> {
+    let start_board = match tags.iter().find(|(name, _)| name == "FEN") {
+        Some((_, fen)) => ChessBoard::from_fen(fen)
+            .map_err(|error| error.with_context(&format!("Game {}, FEN tag", game_number)))?,
+        None => ChessBoard::default(),
+    };
+    let mut chess_board = start_board.clone();
+    let mut moves: Vec<ChessMove> = vec![];
+    for san in sans {
+        let chess_move = parse_san(&chess_board, &san).map_err(|error| {
+            error.with_context(&format!(
+                "Game {}, move {}{} {}",
+                game_number,
+                chess_board.get_fullmove_number(),
+                if chess_board.get_turn() == Color::Black { "..." } else { "." },
+                san
+            ))
+        })?;
+        with_logs_muted(|| chess_board.make_move(&chess_move));
+        moves.push(chess_move);
+    }
+    Ok(PgnGame {
+        tags,
+        start_board,
+        moves,
+        result,
+    })
+}
+
+// Saves a PGN to a file named for the current time, returning its path
+pub fn save_pgn(pgn: &str) -> std::io::Result<PathBuf> {
+    fs::create_dir_all(GAMES_DIR)?;
+    let mut path = PathBuf::from(GAMES_DIR);
+    path.push(format!("{}.pgn", Local::now().format("%Y-%m-%d_%H-%M-%S")));
+    fs::write(&path, pgn)?;
+    Ok(path)
+}
+
+pub fn get_result(outcome: GameOutcome) -> &'static str {
+    match outcome {
+        GameOutcome::InProgress => "*",
+        GameOutcome::Checkmate { winner: Color::White } => "1-0",
+        GameOutcome::Checkmate { winner: Color::Black } => "0-1",
+        GameOutcome::Stalemate
+        | GameOutcome::ThreefoldRepetition
+        | GameOutcome::FiftyMoveRule
+        | GameOutcome::FivefoldRepetition
+        | GameOutcome::SeventyFiveMoveRule => "1/2-1/2",
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use crate::components::square::Square;
+
+    #[test]
+    fn writes_a_finished_game() {
+        let start_board = ChessBoard::default();
+        let mut chess_board = start_board.clone();
+        let mut moves: Vec<ChessMove> = vec![];
+        let scholars_mate = [
+            (Square::E2, Square::E4), (Square::E7, Square::E5), (Square::F1, Square::C4), (Square::B8, Square::C6),
+            (Square::D1, Square::H5), (Square::G8, Square::F6), (Square::H5, Square::F7),
+        ];
+        for (start, target) in scholars_mate {
+            moves.push(chess_board.move_piece(start, target, None).unwrap().chess_move);
+        }
+        let pgn = write_pgn(&start_board, &moves, chess_board.get_outcome());
+        assert!(pgn.starts_with("[Event \"Casual game\"]\n[Site \"?\"]\n[Date \""));
+        assert!(pgn.contains("[Result \"1-0\"]\n\n"));
+        assert!(!pgn.contains("[FEN"));
+        assert!(pgn.ends_with("\n1. e4 e5 2. Bc4 Nc6 3. Qh5 Nf6 4. Qxf7# 1-0\n"));
+    }
+
+    #[test]
+    fn writes_a_game_from_a_custom_position() {
+        let start_board = ChessBoard::from_fen("4k3/8/8/8/8/8/4p3/4K3 b - - 0 40").unwrap();
+        let mut chess_board = start_board.clone();
+        let moves = vec![chess_board.move_piece(Square::E8, Square::D8, None).unwrap().chess_move];
+        let pgn = write_pgn(&start_board, &moves, chess_board.get_outcome());
+        assert!(pgn.contains("[SetUp \"1\"]\n[FEN \"4k3/8/8/8/8/8/4p3/4K3 b - - 0 40\"]\n"));
+        assert!(pgn.ends_with("\n40... Kd8 *\n"));
+    }
+
+    #[test]
+    fn wraps_long_movetext() {
+        let start_board = ChessBoard::default();
+        let mut chess_board = start_board.clone();
+        let mut moves: Vec<ChessMove> = vec![];
+        for _ in 0..10 {
+            for (start, target) in [(Square::G1, Square::F3), (Square::G8, Square::F6), (Square::F3, Square::G1), (Square::F6, Square::G8)] {
+                moves.push(chess_board.move_piece(start, target, None).unwrap().chess_move);
+            }
+        }
+        let pgn = write_pgn(&start_board, &moves, GameOutcome::InProgress);
+        let movetext = pgn.split("\n\n").nth(1).unwrap();
+        assert!(movetext.lines().count() > 1);
+        assert!(movetext.lines().all(|line| line.len() <= MAX_LINE_LEN));
+        assert!(movetext.starts_with("1. Nf3 Nf6 2. Ng1 Ng8 3. Nf3"));
+    }
+
+    #[test]
+    fn reads_back_a_written_game() {
+        let start_board = ChessBoard::from_fen("r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1").unwrap();
+        let mut chess_board = start_board.clone();
+        let mut moves: Vec<ChessMove> = vec![];
+        for _ in 0..12 {
+            let chess_move = chess_board.legal_moves(chess_board.get_turn()).pop().unwrap();
+            chess_board.make_move(&chess_move);
+            moves.push(chess_move);
+        }
+        let games = parse_pgn(&write_pgn(&start_board, &moves, GameOutcome::InProgress)).unwrap();
+        assert_eq!(games.len(), 1);
+        assert_eq!(games[0].start_board.to_fen(), start_board.to_fen());
+        assert_eq!(games[0].moves, moves);
+        assert_eq!(games[0].result, "*");
+    }
+
+    #[test]
+    fn reads_several_games_with_comments_and_variations() {
+        let pgn = "[Event \"First \\\"game\\\"\"]
+[Result \"1-0\"]
+
+1. e4 {best by test} e5 (1... c5 2. Nf3 (2. c3) d6) 2. Bc4 $1 Nc6 3.Qh5 Nf6?? ; oops
+4. Qxf7# 1-0
+
+[Event \"Second game\"]
+[SetUp \"1\"]
+[FEN \"4k3/8/8/8/8/8/8/4K2R w K - 0 1\"]
+
+% an escaped line
+1. O-O Kd7 *
+";
+        let games = parse_pgn(pgn).unwrap();
+        assert_eq!(games.len(), 2);
+        assert_eq!(games[0].get_tag("Event"), Some("First \"game\""));
+        assert_eq!(games[0].moves.len(), 7);
+        assert_eq!(games[0].result, "1-0");
+        assert_eq!(games[1].get_tag("Event"), Some("Second game"));
+        assert_eq!(games[1].moves.len(), 2);
+        assert_eq!(games[1].result, "*");
+    }
+
+    #[test]
+    fn reports_illegal_moves_with_their_move_number() {
+        let error = parse_pgn("1. e4 e5 2. Nf3 Nc6 3. Bb5 Ke6 *").err().unwrap().to_string();
+        assert!(error.starts_with("Game 1, move 3... Ke6:"), "{}", error);
+        let error = parse_pgn("[Event \"a\"]\n\n1. e4 *\n\n[Event \"b\"]\n\n1. Nf3 Nf6 2. Nd5 *").err().unwrap().to_string();
+        assert!(error.starts_with("Game 2, move 2. Nd5:"), "{}", error);
+    }
+
+}
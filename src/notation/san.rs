@@ -0,0 +1,248 @@
+// crate::notation::san
+
+use crate::components::chess_board::ChessBoard;
+use crate::components::chess_board::PROMOTION_TYPES;
+use crate::components::chess_move::ChessMove;
+use crate::components::chess_move::MoveKind;
+use crate::components::piece_type::PieceType;
+use crate::components::square::Square;
+use crate::error::ChessError;
+use crate::utils::logs::with_logs_muted;
+
+
+// Writes a legal move in Standard Algebraic Notation, e.g. "Nbd7", "exd6", "O-O" or "e8=Q+"
+pub fn to_san(chess_board: &ChessBoard, chess_move: &ChessMove) -> String {
+    let mut san = match &chess_move.kind {
+        MoveKind::KingsideCastle => String::from("O-O"),
+        MoveKind::QueensideCastle => String::from("O-O-O"),
+        kind => {
+            let piece = chess_board.borrow_space_contents(chess_move.start);
+            let is_capture = *kind == MoveKind::EnPassant
+                || !chess_board.borrow_space_contents(chess_move.target).is_empty();
+            let mut san = String::new();
+            if piece.is_pawn() {
+                // pawn captures name the file the pawn came from
+                if is_capture {
+                    san.push(Square::get_file_label(chess_move.start.file()));
+                }
+            } else {
+                if let Some(piece_type) = piece.get_type() {
+                    san.push(piece_type.get_letter());
+                }
+                san.push_str(&get_disambiguation(chess_board, chess_move));
+            }
+            if is_capture {
+                san.push('x');
+            }
+            san.push_str(&chess_move.target.to_string());
+            if let MoveKind::Promotion(piece_type) = kind {
+                san.push('=');
+                san.push(piece_type.get_letter());
+            }
+            san
+        }
+    };
+
+    // Mark checks and checkmates
+    let mut board_after = chess_board.clone();
+    with_logs_muted(|| board_after.make_move(chess_move));
+    if board_after.is_in_check(board_after.get_turn()) {
+        san.push(if board_after.legal_moves(board_after.get_turn()).is_empty() { '#' } else { '+' });
+    }
+
+    san
+}
+
+// Finds the legal move that a move in Standard Algebraic Notation describes
+pub fn parse_san(chess_board: &ChessBoard, san: &str) -> Result<ChessMove, ChessError> {
+    let legal_moves = chess_board.legal_moves(chess_board.get_turn());
+    let trimmed = san.trim().trim_end_matches(['+', '#', '!', '?']);
+
+    // Castling
+    let castle_kind = match trimmed {
+        "O-O" | "0-0" => Some(MoveKind::KingsideCastle),
+        "O-O-O" | "0-0-0" => Some(MoveKind::QueensideCastle),
+        _ => None,
+    };
+    if let Some(kind) = castle_kind {
+        return match legal_moves.into_iter().find(|chess_move| chess_move.kind == kind) {
+            Some(chess_move) => Ok(chess_move),
+            None => Err(ChessError::IllegalMove(format!("\"{}\" is not a legal castle here.", san))),
+        };
+    }
+
+    // Piece letter, if not a pawn move
+    let mut chars: Vec<char> = trimmed.chars().collect();
+    let piece_type = match chars.first() {
+        Some(letter) if letter.is_ascii_uppercase() => {
+            let piece_type = PieceType::from_letter(*letter)
+                .filter(|piece_type| *piece_type != PieceType::Pawn)
+                .ok_or(ChessError::Parse(format!("\"{}\" has the invalid piece letter '{}'.", san, letter)))?;
+            chars.remove(0);
+            piece_type
+        },
+        _ => PieceType::Pawn,
+    };
+
+    // Promotion piece, also accepted without the '=' (e.g. "e8Q")
+    let mut promotion: Option<PieceType> = None;
+    if let Some(index) = chars.iter().position(|label| *label == '=') {
+        let letter = chars.get(index + 1).ok_or(ChessError::Parse(format!("\"{}\" is missing its promotion piece.", san)))?;
+        promotion = Some(
+            get_promotion_type(letter.to_ascii_uppercase())
+                .ok_or(ChessError::Parse(format!("\"{}\" has the invalid promotion piece '{}'.", san, letter)))?
+        );
+        chars.truncate(index);
+    } else if piece_type == PieceType::Pawn && chars.last().is_some_and(|letter| letter.is_ascii_uppercase()) {
+        let letter = chars.pop().unwrap();
+        promotion = Some(
+            get_promotion_type(letter)
+                .ok_or(ChessError::Parse(format!("\"{}\" has the invalid promotion piece '{}'.", san, letter)))?
+        );
+    }
+
+    // Target space, with the optional start file and/or rank before it
+    chars.retain(|label| *label != 'x');
+    if chars.len() < 2 || chars.len() > 4 {
+        return Err(ChessError::Parse(format!("\"{}\" is not a valid SAN move.", san)));
+    }
+    let target = Square::from_labels(chars[chars.len() - 2], chars[chars.len() - 1])
+        .map_err(|_| ChessError::Parse(format!("\"{}\" does not name a valid target space.", san)))?;
+    let mut start_file: Option<u8> = None;
+    let mut start_rank: Option<u8> = None;
+    for label in &chars[..chars.len() - 2] {
+        match label {
+            'a'..='h' if start_file.is_none() => start_file = Square::get_file(*label),
+            '1'..='8' if start_rank.is_none() => start_rank = Square::get_rank(*label),
+            _ => return Err(ChessError::Parse(format!("\"{}\" has the invalid disambiguation '{}'.", san, label))),
+        }
+    }
+
+    let candidates: Vec<ChessMove> = legal_moves.into_iter()
+        .filter(|chess_move| {
+            chess_move.target == target
+            && chess_board.borrow_space_contents(chess_move.start).get_type() == Some(piece_type)
+            && start_file.is_none_or(|file| file == chess_move.start.file())
+            && start_rank.is_none_or(|rank| rank == chess_move.start.rank())
+            && match &chess_move.kind {
+                MoveKind::Promotion(piece_type) => promotion == Some(*piece_type),
+                _ => promotion.is_none(),
+            }
+        })
+        .collect();
+    match candidates.len() {
+        0 => Err(ChessError::IllegalMove(format!("\"{}\" is not a legal move here.", san))),
+        1 => Ok(candidates.into_iter().next().unwrap()),
+        _ => Err(ChessError::IllegalMove(format!("\"{}\" is ambiguous here.", san))),
+    }
+}
+
+// Reads the letter of a piece a pawn can promote to
+fn get_promotion_type(letter: char) -> Option<PieceType> {
+    PieceType::from_letter(letter).filter(|piece_type| PROMOTION_TYPES.contains(piece_type))
+}
+
+// The start file and/or rank needed to tell this move apart from moves by other pieces of the same type to the same target
+fn get_disambiguation(chess_board: &ChessBoard, chess_move: &ChessMove) -> String {
+    let piece_type = chess_board.borrow_space_contents(chess_move.start).get_type();
+    let rivals: Vec<ChessMove> = chess_board.legal_moves(chess_board.get_turn())
+        .into_iter()
+        .filter(|other_move| {
+            other_move.target == chess_move.target
+            && other_move.start != chess_move.start
+            && chess_board.borrow_space_contents(other_move.start).get_type() == piece_type
+        })
+        .collect();
+    if rivals.is_empty() {
+        String::new()
+    } else if rivals.iter().all(|other_move| other_move.start.file() != chess_move.start.file()) {
+        Square::get_file_label(chess_move.start.file()).to_string()
+    } else if rivals.iter().all(|other_move| other_move.start.rank() != chess_move.start.rank()) {
+        Square::get_rank_label(chess_move.start.rank()).to_string()
+    } else {
+        chess_move.start.to_string()
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    fn assert_san(fen: &str, start: Square, target: Square, kind: MoveKind, expected_san: &str) {
+        let chess_board = ChessBoard::from_fen(fen).unwrap();
+        let chess_move = ChessMove::construct(start, target, kind);
+        assert!(chess_board.legal_moves(chess_board.get_turn()).contains(&chess_move));
+        assert_eq!(to_san(&chess_board, &chess_move), expected_san);
+        assert_eq!(parse_san(&chess_board, expected_san), Ok(chess_move));
+    }
+
+    #[test]
+    fn writes_pawn_and_piece_moves() {
+        let start = "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1";
+        assert_san(start, Square::E2, Square::E4, MoveKind::Normal, "e4");
+        assert_san(start, Square::G1, Square::F3, MoveKind::Normal, "Nf3");
+    }
+
+    #[test]
+    fn writes_captures_and_en_passant() {
+        let fen = "rnbqkbnr/ppp1p1pp/8/3pPp2/8/8/PPPP1PPP/RNBQKBNR w KQkq f6 0 3";
+        assert_san(fen, Square::E5, Square::F6, MoveKind::EnPassant, "exf6");
+        assert_san("4k3/8/8/3p4/4P3/8/8/4K3 w - - 0 1", Square::E4, Square::D5, MoveKind::Normal, "exd5");
+    }
+
+    #[test]
+    fn writes_castling() {
+        let fen = "r3k2r/8/8/8/8/8/8/R3K2R w KQkq - 0 1";
+        assert_san(fen, Square::E1, Square::G1, MoveKind::KingsideCastle, "O-O");
+        assert_san(fen, Square::E1, Square::C1, MoveKind::QueensideCastle, "O-O-O");
+    }
+
+    #[test]
+    fn writes_promotions_checks_and_checkmates() {
+        assert_san("8/4P3/8/8/8/8/k7/4K3 w - - 0 1", Square::E7, Square::E8, MoveKind::Promotion(PieceType::Queen), "e8=Q");
+        assert_san("8/4P3/8/8/8/8/8/k3K3 w - - 0 1", Square::E7, Square::E8, MoveKind::Promotion(PieceType::Rook), "e8=R");
+        assert_san("k7/8/8/8/8/8/8/1R2K3 w - - 0 1", Square::B1, Square::A1, MoveKind::Normal, "Ra1+");
+        assert_san("k7/8/1K6/8/8/8/8/7R w - - 0 1", Square::H1, Square::H8, MoveKind::Normal, "Rh8#");
+    }
+
+    #[test]
+    fn disambiguates_by_file_then_rank_then_both() {
+        assert_san("4k3/8/8/8/8/8/8/R4RK1 w - - 0 1", Square::A1, Square::D1, MoveKind::Normal, "Rad1");
+        assert_san("4k3/R7/8/8/8/8/8/R3K3 w - - 0 1", Square::A1, Square::A4, MoveKind::Normal, "R1a4");
+        assert_san("2k5/8/8/8/4Q2Q/8/K7/7Q w - - 0 1", Square::H4, Square::E1, MoveKind::Normal, "Qh4e1");
+    }
+
+    #[test]
+    fn rejects_illegal_ambiguous_and_malformed_moves() {
+        let chess_board = ChessBoard::from_fen("4k3/8/8/8/8/8/8/R4RK1 w - - 0 1").unwrap();
+        assert!(parse_san(&chess_board, "Rd1").unwrap_err().to_string().contains("ambiguous"));
+        assert!(parse_san(&chess_board, "Re1").unwrap_err().to_string().contains("ambiguous"));
+        assert!(parse_san(&chess_board, "Rfd1").is_ok());
+        assert!(parse_san(&chess_board, "Rd2").unwrap_err().to_string().contains("not a legal move"));
+        assert!(parse_san(&chess_board, "Ke2").is_err());
+        assert!(parse_san(&chess_board, "O-O").is_err());
+        assert!(parse_san(&chess_board, "Zd1").is_err());
+        assert!(parse_san(&chess_board, "Ri9").is_err());
+        assert!(parse_san(&chess_board, "").is_err());
+        let chess_board = ChessBoard::from_fen("8/4P3/8/8/8/8/k7/4K3 w - - 0 1").unwrap();
+        assert!(parse_san(&chess_board, "e8").is_err());
+        assert!(parse_san(&chess_board, "e8=K").is_err());
+        assert!(parse_san(&chess_board, "e8=N").is_ok());
+    }
+
+    #[test]
+    fn reads_loosely_written_moves() {
+        let chess_board = ChessBoard::from_fen("8/4P3/8/8/8/8/k7/4K3 w - - 0 1").unwrap();
+        let queen_promotion = ChessMove::construct(Square::E7, Square::E8, MoveKind::Promotion(PieceType::Queen));
+        assert_eq!(parse_san(&chess_board, "e8Q"), Ok(queen_promotion.clone()));
+        assert_eq!(parse_san(&chess_board, "e8=q"), Ok(queen_promotion.clone()));
+        assert_eq!(parse_san(&chess_board, " e8=Q+ "), Ok(queen_promotion));
+        let chess_board = ChessBoard::from_fen("r3k2r/8/8/8/8/8/8/R3K2R b KQkq - 0 1").unwrap();
+        assert_eq!(parse_san(&chess_board, "0-0-0"), Ok(ChessMove::construct(Square::E8, Square::C8, MoveKind::QueensideCastle)));
+        assert_eq!(parse_san(&chess_board, "Ra8xa1"), Ok(ChessMove::construct(Square::A8, Square::A1, MoveKind::Normal)));
+        assert_eq!(parse_san(&chess_board, "Rxa1!?"), Ok(ChessMove::construct(Square::A8, Square::A1, MoveKind::Normal)));
+    }
+
+}
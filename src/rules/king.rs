@@ -2,52 +2,30 @@
 
 use crate::components::chess_board::ChessBoard;
 use crate::components::chess_piece::ChessPiece;
+use crate::components::color::Color;
+use crate::components::square::FILE_A;
+use crate::components::square::FILE_B;
+use crate::components::square::FILE_G;
+use crate::components::square::FILE_H;
+use crate::components::square::Square;
 use crate::utils::logs::log;
+use crate::utils::logs::with_logs_muted;
 
-pub fn is_valid_move(
-    start_rank: u8,
-    start_file: u8,
-    target_rank: u8,
-    target_file: u8,
-    chess_board: &ChessBoard
-) -> bool {
+pub fn is_valid_move(start: Square, target: Square, chess_board: &ChessBoard) -> bool {
     log("INFO", "Validating a potential king move...");
-    log("NOTE", "crate::rules::king::is_valid_move does not currently ensure that the space the king moves to is unthreatened.");
-
-    // TODO: CHECK FOR AND ALLOW CASTLING (have to figure out a way to move the rook too)
-    // TODO: ENSURE THERE'S NO ACTIVE THREAT ON TARGET
-
-    // Ensure start is on the board
-    if start_rank >= 8 || start_file >= 8 {
-        log(
-            "ERROR",
-            format!(
-                "crate::rules::king::is_valid_move received an invalid starting position: file #{}, rank #{}",
-                start_file,
-                start_rank
-            )
-        );
-        panic!("Invalid starting rank or file: rank {}, file {}", start_rank, start_file);
-    }
-    log("INFO", "The start position is on the board.");
 
-    // Ensure target is on the board
-    if target_rank >= 8 || target_file >= 8 {
-        log("INFO", "Bad move: The target position is not on the board.");
-        return false;
-    }
-    log("INFO", "The target position is on the board");
+    // Note: whether the target is threatened is checked by ChessBoard::move_piece, as with every other piece
 
     // Ensure start and target are different spaces
-    if start_rank == target_rank && start_file == target_file {
+    if start == target {
         log("INFO", "Bad move: The start and target positions are the same.");
         return false;
     }
     log("INFO", "The start and target positions are different.");
 
     // Ensure target doesn't contain a friendly piece
-    let start_space: &ChessPiece = chess_board.borrow_space_contents(start_rank, start_file);
-    let target_space: &ChessPiece = chess_board.borrow_space_contents(target_rank, target_file);
+    let start_space: &ChessPiece = chess_board.borrow_space_contents(start);
+    let target_space: &ChessPiece = chess_board.borrow_space_contents(target);
     if start_space.get_color() == target_space.get_color() {
         log("INFO", "Bad move: The target position contains a friendly piece.");
         return false;
@@ -55,33 +33,35 @@ pub fn is_valid_move(
     log("INFO", "The target position does not contain a friendly piece.");
 
     // For a target greater than one square away:
-    if ( (start_file as i8 - target_file as i8).abs() > 1 )
-    || ( (start_rank as i8 - target_rank as i8).abs() > 1 ) {
+    if start.file().abs_diff(target.file()) > 1 || start.rank().abs_diff(target.rank()) > 1 {
         log("INFO", "The target position is not within one file and one rank of the starting position.");
         
         // Ensure that the target is in the same rank as the start
-        if start_rank != target_rank {
+        if start.rank() != target.rank() {
             log("INFO", "Bad move: The target position is not in the same rank as the starting position.");
             return false;
         }
 
         // Ensure that the target is exactly two squares to the left or right of the start
-        if (start_file as i8 - target_file as i8).abs() != 2 {
+        if start.file().abs_diff(target.file()) != 2 {
             log("INFO", "Bad move: The target position is not exactly two squares left or right of the starting position.");
             return false;
         }
 
         // Ensure that the king has not yet moved
-        if chess_board.borrow_space_contents(start_rank, start_file).has_moved() {
+        if chess_board.borrow_space_contents(start).has_moved() {
             log("INFO", "Bad move: The king has already been moved.");
             return false;
         }
 
         // For a queenside castle:
-        if target_file < start_file {
+        if target.file() < start.file() {
             log("INFO", "Detected a queenside castle attempt.");
 
-            let rook_spot = chess_board.borrow_space_contents(start_rank, ChessBoard::get_file(String::from("a")));
+            let rook_spot = match Square::construct(start.rank(), FILE_A) {
+                Some(rook_square) => chess_board.borrow_space_contents(rook_square),
+                None => return false,
+            };
 
             // Ensure there is a rook in file a
             if !rook_spot.is_rook() {
@@ -91,7 +71,7 @@ pub fn is_valid_move(
             log("INFO", "File a in this rank contains a rook.");
 
             // Ensure the rook is friendly
-            if rook_spot.get_color() != chess_board.borrow_space_contents(start_rank, start_file).get_color() {
+            if rook_spot.get_color() != chess_board.borrow_space_contents(start).get_color() {
                 log("INFO", "Bad move: The rook in file a is not friendly.");
                 return false;
             }
@@ -105,31 +85,28 @@ pub fn is_valid_move(
             log("INFO", "The rook has not moved.");
 
             // Ensure every space between the king and the rook is empty
-            let mut file = ChessBoard::get_file(String::from("b"));
-            while file < start_file {
-                if !chess_board.borrow_space_contents(start_rank, file).is_empty() {
+            let mut current = start.offset(0, -1);
+            while let Some(current_square) = current
+            && current_square.file() >= FILE_B {
+                if !chess_board.borrow_space_contents(current_square).is_empty() {
                     log(
                         "INFO",
-                        format!(
-                            "Bad move: The position {}{}, between the king and the rook, is not empty.",
-                            ChessBoard::get_file_label(file),
-                            ChessBoard::get_rank_label(start_rank)
-                        )
+                        format!("Bad move: The position {}, between the king and the rook, is not empty.", current_square)
                     );
                     return false;
                 }
-                file += 1;
+                current = current_square.offset(0, -1);
             }
             log("INFO", "All positions between the king and the rook are empty.");
 
-            // TODO: add checks for king safety
-            log("NOTE", "Not currently checking for king safety in kingside castling.");
-
         // For a kingside castle:
         } else {
             log("INFO", "Detected a kingside castle attempt.");
 
-            let rook_spot = chess_board.borrow_space_contents(start_rank, ChessBoard::get_file(String::from("h")));
+            let rook_spot = match Square::construct(start.rank(), FILE_H) {
+                Some(rook_square) => chess_board.borrow_space_contents(rook_square),
+                None => return false,
+            };
 
             // Ensure there is a rook in file h
             if !rook_spot.is_rook() {
@@ -139,7 +116,7 @@ pub fn is_valid_move(
             log("INFO", "File h in this rank contains a rook.");
 
             // Ensure the rook is friendly
-            if rook_spot.get_color() != chess_board.borrow_space_contents(start_rank, start_file).get_color() {
+            if rook_spot.get_color() != chess_board.borrow_space_contents(start).get_color() {
                 log("INFO", "Bad move: The rook in file h is not friendly.");
                 return false;
             }
@@ -153,26 +130,42 @@ pub fn is_valid_move(
             log("INFO", "The rook has not moved.");
 
             // Ensure every space between the king and the rook is empty
-            let mut file = ChessBoard::get_file(String::from("g"));
-            while file < start_file {
-                if !chess_board.borrow_space_contents(start_rank, file).is_empty() {
+            let mut current = start.offset(0, 1);
+            while let Some(current_square) = current
+            && current_square.file() <= FILE_G {
+                if !chess_board.borrow_space_contents(current_square).is_empty() {
                     log(
                         "INFO",
-                        format!(
-                            "Bad move: The position {}{}, between the king and the rook, is not empty.",
-                            ChessBoard::get_file_label(file),
-                            ChessBoard::get_rank_label(start_rank)
-                        )
+                        format!("Bad move: The position {}, between the king and the rook, is not empty.", current_square)
                     );
                     return false;
                 }
-                file -= 1;
+                current = current_square.offset(0, 1);
             }
             log("INFO", "All positions between the king and the rook are empty.");
+        }
 
-            // TODO: add checks for king safety
-            log("NOTE", "Not currently checking for king safety in queenside castling.");
+        // Ensure the king is not castling out of, through, or into check
+        let enemy_color = if chess_board.borrow_space_contents(start).is_black() { Color::White } else { Color::Black };
+        let file_increment: i8 = if target.file() > start.file() { 1 } else { -1 };
+        let mut current = Some(start);
+        while let Some(current_square) = current {
+            if chess_board.is_space_attacked(current_square, enemy_color) {
+                log(
+                    "INFO",
+                    format!(
+                        "Bad move: The position {}, which the king would castle out of, through, or into, is attacked.",
+                        current_square
+                    )
+                );
+                return false;
+            }
+            if current_square == target {
+                break;
+            }
+            current = current_square.offset(0, file_increment);
         }
+        log("INFO", "None of the positions the king would castle out of, through, or into are attacked.");
     
     }
 
@@ -181,3 +174,31 @@ pub fn is_valid_move(
     return true;
     
 }
+
+pub fn get_valid_moves(start: Square, chess_board: &ChessBoard) -> Vec<Square> {
+    let start_space: &ChessPiece = chess_board.borrow_space_contents(start);
+    let mut moves: Vec<Square> = vec![];
+
+    // Every space within one file and one rank
+    for rank_increment in -1..=1 {
+        for file_increment in -1..=1 {
+            if (rank_increment, file_increment) != (0, 0)
+            && let Some(target) = start.offset(rank_increment, file_increment)
+            && chess_board.borrow_space_contents(target).get_color() != start_space.get_color() {
+                moves.push(target);
+            }
+        }
+    }
+
+    // Castling, which has enough special cases to defer to is_valid_move
+    if !start_space.has_moved() {
+        for file_increment in [-2, 2] {
+            if let Some(target) = start.offset(0, file_increment)
+            && with_logs_muted(|| is_valid_move(start, target, chess_board)) {
+                moves.push(target);
+            }
+        }
+    }
+
+    return moves;
+}
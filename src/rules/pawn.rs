@@ -2,43 +2,27 @@
 
 use crate::components::chess_board::ChessBoard;
 use crate::components::chess_piece::ChessPiece;
+use crate::components::square::RANK_2;
+use crate::components::square::RANK_7;
+use crate::components::square::Square;
 use crate::utils::logs::log;
 
-pub fn is_valid_move(start_rank: u8, start_file: u8, target_rank: u8, target_file: u8, chess_board: &ChessBoard, last_move: &Option<((u8, u8), (u8, u8))>) -> bool {
+pub fn is_valid_move(start: Square, target: Square, chess_board: &ChessBoard) -> bool {
 
     log("INFO", "Validating a potential pawn move...");
-    log("NOTE", "crate::rules::king::is_valid_move does not currently check for and allow pawn promotion.");
-
-    // TODO: CHECK FOR AND ALLOW EN PASSANT (have to figure out a way to capture the other pawn too)
-    // TODO: figure out a way to implement pawn promotion
-
-    // Ensure start is on the board
-    if start_rank >= 8 || start_file >= 8 {
-        log(
-            "ERROR",
-            format!("crate::rules::pawn::is_valid_move received an invalid starting position: file #{}, rank #{}", start_file, start_rank)
-        );
-        panic!("Invalid starting rank or file: rank {}, file {}", start_rank, start_file);
-    }
-    log("INFO", "The start position is on the board.");
 
-    // Ensure target is on the board
-    if target_rank >= 8 || target_file >= 8 {
-        log("INFO", "Bad move: The target position is not on the board.");
-        return false;
-    }
-    log("INFO", "The target position is on the board");
+    // Note: the choice of piece for a promotion is checked by ChessBoard::move_piece
 
     // Ensure start and target are different spaces
-    if start_rank == target_rank && start_file == target_file {
+    if start == target {
         log("INFO", "Bad move: The start and target positions are the same.");
         return false;
     }
     log("INFO", "The start and target positions are different.");
 
     // Ensure target doesn't contain a friendly piece
-    let start_space: &ChessPiece = chess_board.borrow_space_contents(start_rank, start_file);
-    let target_space: &ChessPiece = chess_board.borrow_space_contents(target_rank, target_file);
+    let start_space: &ChessPiece = chess_board.borrow_space_contents(start);
+    let target_space: &ChessPiece = chess_board.borrow_space_contents(target);
     if start_space.get_color() == target_space.get_color() {
         log("INFO", "Bad move: The target position contains a friendly piece.");
         return false;
@@ -46,7 +30,7 @@ pub fn is_valid_move(start_rank: u8, start_file: u8, target_rank: u8, target_fil
     log("INFO", "The target position does not contain a friendly piece.");
 
     // Ensure target is within 1 file either direction of start
-    if (start_file as i8 - target_file as i8).abs() > 1 {
+    if start.file().abs_diff(target.file()) > 1 {
         log("INFO", "Bad move: The target position is not within one file either direction of the starting position.");
         return false;
     }
@@ -57,33 +41,40 @@ pub fn is_valid_move(start_rank: u8, start_file: u8, target_rank: u8, target_fil
         log("INFO", "The piece to move is a white pawn.");
 
         // For a space in the same file:
-        if start_file == target_file {
+        if start.file() == target.file() {
             log("INFO", "The target position is in the same file as the starting position.");
             
             // Ensure target doesn't contain a piece
-            if !chess_board.borrow_space_contents(target_rank, target_file).is_empty() {
+            if !chess_board.borrow_space_contents(target).is_empty() {
                 log("INFO", "Bad move: The target position contains a piece.");
                 return false;
             }
             log("INFO", "The target position does not contain a piece.");
 
             // For a pawn still in home row (rank 2):
-            if start_rank == ChessBoard::get_rank(String::from("2")) {
+            if start.rank() == RANK_2 {
                 log("INFO", "The start position is in the pawn's home row.");
 
                 // Ensure target is within 2 spaces ahead of start
-                if target_rank >= start_rank || target_rank < start_rank - 2 {
+                if target.rank() >= start.rank() || target.rank() + 2 < start.rank() {
                     log("INFO", "Bad move: The target position is not within two spaces ahead of the starting position.");
                     return false;
                 }
                 log("INFO", "The target position is within two spaces ahead of the starting position.");
 
+                // Ensure a double move doesn't jump over a piece
+                if target.rank() + 2 == start.rank()
+                && chess_board.borrow_offset_space_contents(start, -1, 0).is_some_and(|piece| !piece.is_empty()) {
+                    log("INFO", "Bad move: The position between the start and target positions is not empty.");
+                    return false;
+                }
+
             // For a pawn not still in home row:
             } else {
                 log("INFO", "The start position is not in the pawn's home row.");
 
                 // Ensure target is the space ahead of start
-                if target_rank != start_rank - 1 {
+                if target.rank() + 1 != start.rank() {
                     log("INFO", "Bad move: The target position is not the space immediately ahead of the starting position.");
                     return false;
                 }
@@ -95,17 +86,17 @@ pub fn is_valid_move(start_rank: u8, start_file: u8, target_rank: u8, target_fil
             log("INFO", "The target position is in an adjacent file to the starting position.");
 
             // Ensure target is in the rank ahead of start
-            if target_rank != start_rank - 1 {
+            if target.rank() + 1 != start.rank() {
                 log("INFO", "Bad move: The target position is not in the rank immediately ahead of the starting position.");
                 return false;
             }
             log("INFO", "The target position is in the rank immediately ahead of the starting position.");
 
             // For target being empty:
-            if chess_board.borrow_space_contents(target_rank, target_file).is_empty() {
+            if chess_board.borrow_space_contents(target).is_empty() {
                 log("INFO", "The target position does not contain a piece.");
                 // Ensure the move is an en passant
-                match last_move {
+                match chess_board.get_last_move() {
                     None => {
                         log("INFO", "Bad move: This is the first move, cannot be en passant.");
                         return false;
@@ -113,20 +104,17 @@ pub fn is_valid_move(start_rank: u8, start_file: u8, target_rank: u8, target_fil
                     Some(_move) => {
                         log("INFO", "There was a previous move.");
                         // Ensure there is a pawn in the correct relative space
-                        if !chess_board.borrow_space_contents(target_rank + 1, target_file).is_pawn() {
+                        let passed_space = start.with_file_of(target);
+                        if !chess_board.borrow_space_contents(passed_space).is_pawn() {
                             log(
                                 "INFO", 
-                                format!(
-                                    "Bad move: The position {}{}, below the target position, is not a pawn.",
-                                    ChessBoard::get_file_label(target_file),
-                                    ChessBoard::get_rank_label(target_rank + 1)
-                                )
+                                format!("Bad move: The position {}, below the target position, is not a pawn.", passed_space)
                             );
                             return false
                         }
 
                         // Ensure pawn just moved there with a double move
-                        if *_move != ((target_file, target_rank - 1), (target_file, target_rank + 1)) {
+                        if Some(*_move) != target.offset(-1, 0).map(|passed_start| (passed_start, passed_space)) {
                             log("INFO", "Bad move: Pawn has not just moved with a double move.");
                             return false;
                         }
@@ -141,33 +129,40 @@ pub fn is_valid_move(start_rank: u8, start_file: u8, target_rank: u8, target_fil
         log("INFO", "The piece to move is a black pawn.");
 
         // For a space in the same file:
-        if start_file == target_file {
+        if start.file() == target.file() {
             log("INFO", "The target position is in the same file as the starting position.");
 
             // Ensure target doesn't contain a piece
-            if !chess_board.borrow_space_contents(target_rank, target_file).is_empty() {
+            if !chess_board.borrow_space_contents(target).is_empty() {
                 log("INFO", "Bad move: The target position contains a piece.");
                 return false;
             }
             log("INFO", "The target position does not contain a piece.");
 
             // For a pawn still in home row (rank 7):
-            if start_rank == ChessBoard::get_rank(String::from("7")) {
+            if start.rank() == RANK_7 {
                 log("INFO", "The starting position is in the pawn's home row.");
 
                 // Ensure target is within 2 spaces ahead of start
-                if target_rank <= start_rank || target_rank > start_rank + 2 {
+                if target.rank() <= start.rank() || target.rank() > start.rank() + 2 {
                     log("INFO", "Bad move: The target position is not within two spaces ahead of the starting position.");
                     return false;
                 }
                 log("INFO", "The target position is within two spaces ahead of the starting position.");
 
+                // Ensure a double move doesn't jump over a piece
+                if target.rank() == start.rank() + 2
+                && chess_board.borrow_offset_space_contents(start, 1, 0).is_some_and(|piece| !piece.is_empty()) {
+                    log("INFO", "Bad move: The position between the start and target positions is not empty.");
+                    return false;
+                }
+
             // For a pawn not still in home row:
             } else {
                 log("INFO", "The starting position is not in the pawn's home row.");
 
                 // Ensure target is the space ahead of start
-                if target_rank != start_rank + 1 {
+                if target.rank() != start.rank() + 1 {
                     log("INFO", "Bad move: The target position is not the space immediately ahead of the starting position.");
                     return false;
                 }
@@ -179,17 +174,17 @@ pub fn is_valid_move(start_rank: u8, start_file: u8, target_rank: u8, target_fil
             log("INFO", "The target position is in an adjacent file to the starting position.");
 
             // Ensure target is in the rank ahead of start
-            if target_rank != start_rank + 1 {
+            if target.rank() != start.rank() + 1 {
                 log("INFO", "Bad move: The target position is not in the rank immediately ahead of the starting position.");
                 return false;
             }
             log("INFO", "The target position is in the rank immediately ahead of the starting position.");
 
             // For target being empty:
-            if chess_board.borrow_space_contents(target_rank, target_file).is_empty() {
+            if chess_board.borrow_space_contents(target).is_empty() {
                 log("INFO", "The target position does not contain a piece.");
                 // Ensure the move is an en passant
-                match last_move {
+                match chess_board.get_last_move() {
                     None => {
                         log("INFO", "Bad move: This is the first move, cannot be en passant.");
                         return false;
@@ -197,20 +192,17 @@ pub fn is_valid_move(start_rank: u8, start_file: u8, target_rank: u8, target_fil
                     Some(_move) => {
                         log("INFO", "There was a previous move.");
                         // Ensure there is a pawn in the correct relative space
-                        if !chess_board.borrow_space_contents(target_rank - 1, target_file).is_pawn() {
+                        let passed_space = start.with_file_of(target);
+                        if !chess_board.borrow_space_contents(passed_space).is_pawn() {
                             log(
                                 "INFO", 
-                                format!(
-                                    "Bad move: The position {}{}, below the target position, is not a pawn.",
-                                    ChessBoard::get_file_label(target_file),
-                                    ChessBoard::get_rank_label(target_rank - 1)
-                                )
+                                format!("Bad move: The position {}, below the target position, is not a pawn.", passed_space)
                             );
                             return false
                         }
 
                         // Ensure pawn just moved there with a double move
-                        if *_move != ((target_file, target_rank + 1), (target_file, target_rank - 1)) {
+                        if Some(*_move) != target.offset(1, 0).map(|passed_start| (passed_start, passed_space)) {
                             log("INFO", "Bad move: Pawn has not just moved with a double move.");
                             return false;
                         }
@@ -226,3 +218,51 @@ pub fn is_valid_move(start_rank: u8, start_file: u8, target_rank: u8, target_fil
     return true;
     
 }
+
+pub fn get_valid_moves(start: Square, chess_board: &ChessBoard) -> Vec<Square> {
+    let start_space: &ChessPiece = chess_board.borrow_space_contents(start);
+    let mut moves: Vec<Square> = vec![];
+
+    // White pawns move towards rank 8 (lower rank numbers), black pawns towards rank 1
+    let (rank_increment, home_rank): (i8, u8) = if start_space.is_white() {
+        (-1, RANK_2)
+    } else {
+        (1, RANK_7)
+    };
+    let ahead = match start.offset(rank_increment, 0) {
+        Some(ahead) => ahead,
+        None => return moves,
+    };
+
+    // Moving ahead, by two spaces from the home row
+    if chess_board.borrow_space_contents(ahead).is_empty() {
+        moves.push(ahead);
+        if start.rank() == home_rank
+        && let Some(two_ahead) = ahead.offset(rank_increment, 0)
+        && chess_board.borrow_space_contents(two_ahead).is_empty() {
+            moves.push(two_ahead);
+        }
+    }
+
+    // Capturing diagonally, including en passant on a pawn that has just moved two spaces past
+    for file_increment in [-1, 1] {
+        let target = match ahead.offset(0, file_increment) {
+            Some(target) => target,
+            None => continue,
+        };
+        let target_space = chess_board.borrow_space_contents(target);
+        let passed_space = start.with_file_of(target);
+        if !target_space.is_empty() {
+            if target_space.get_color() != start_space.get_color() {
+                moves.push(target);
+            }
+        } else if let Some((last_start, last_target)) = *chess_board.get_last_move()
+        && last_target == passed_space
+        && Some(last_start) == target.offset(rank_increment, 0)
+        && chess_board.borrow_space_contents(passed_space).is_pawn() {
+            moves.push(target);
+        }
+    }
+
+    return moves;
+}
@@ -0,0 +1,181 @@
+// crate::tui
+
+use std::io::Read;
+use std::io::Write;
+use std::sync::atomic::AtomicBool;
+use std::time::Duration;
+
+use text_io::try_read;
+
+use chess::components::chess_game::ChessGame;
+use chess::components::chess_move::ChessMove;
+use chess::components::color::Color;
+use chess::engine::external::ExternalEngine;
+use chess::engine::search::SearchLimits;
+use chess::engine::search::search;
+use chess::engine::transposition::TranspositionTable;
+use chess::error::ChessError;
+use chess::notation::coordinate::parse_coordinate;
+use chess::notation::san::parse_san;
+use chess::notation::san::to_san;
+use chess::utils::logs::log;
+use chess::utils::logs::set_logs_echoed;
+
+use crate::cli::Player;
+use crate::cli::Players;
+
+const HELP: &str = "Type a move as coordinates (e2e4, e7e8q) or in SAN (e4, Nf3, O-O, exd8=Q).
+Other commands: undo, redo, draw (to claim one), fen, pgn, help, quit.
+\"white engine\" or \"black engine\" hands a side to the engine, \"white human\" or \"black human\" takes it back.";
+
+
+// Play in the terminal: print the board, read moves and commands from stdin until it ends or the player quits
+pub fn run(mut chess_game: ChessGame, mut players: Players, engine_limits: SearchLimits, mut external_engine: Option<ExternalEngine>) {
+    // stdout is the game now, so logs only go to the log file
+    set_logs_echoed(false);
+    log("INFO", format!("Starting a terminal game from the position {}", chess_game.borrow_board().to_fen()));
+
+    println!("{}", HELP);
+    let mut input = std::io::stdin().lock().bytes().map_while(Result::ok).peekable();
+    let mut show_board = true;
+    loop {
+        // the engine answers as soon as it is its turn
+        let turn = chess_game.borrow_board().get_turn();
+        if players.get(turn) == Player::Engine && !chess_game.get_outcome().is_over() {
+            let played = get_engine_move(&chess_game, engine_limits, &mut external_engine)
+                .and_then(|chess_move| play(&mut chess_game, &chess_move));
+            match played {
+                Ok(san) => println!("The engine played {}.", san),
+                // hand the side back rather than asking the engine again
+                Err(error) => {
+                    println!("{}", error);
+                    players.set(turn, Player::Human);
+                    println!("The {} now plays {}.", Player::Human, turn);
+                }
+            }
+            show_board = true;
+        }
+        if show_board {
+            print_position(&chess_game);
+            show_board = false;
+        }
+        print!("> ");
+        // the prompt has no newline, so it would otherwise wait in the buffer until after the move is typed
+        if let Err(error) = std::io::stdout().flush() {
+            log("ERROR", format!("Couldn't show the prompt: {}", error));
+        }
+
+        // read a line, stopping once the input runs out
+        if input.peek().is_none() {
+            println!();
+            break;
+        }
+        let line: String = match try_read!("{}\n", &mut input) {
+            Ok(line) => line,
+            Err(error) => {
+                println!("Couldn't read that line: {}", error);
+                continue;
+            }
+        };
+        let command = line.trim();
+
+        match command {
+            "" => (),
+            "quit" | "exit" => break,
+            "help" => println!("{}", HELP),
+            "fen" => println!("{}", chess_game.borrow_board().to_fen()),
+            "pgn" => println!("{}", chess_game.to_pgn()),
+            "undo" => match step(&mut chess_game, players, ChessGame::undo_move) {
+                true => show_board = true,
+                false => println!("There is no move to undo."),
+            },
+            "redo" => match step(&mut chess_game, players, ChessGame::redo_move) {
+                true => show_board = true,
+                false => println!("There is no move to redo."),
+            },
+            "draw" => match chess_game.claim_draw() {
+                true => show_board = true,
+                false => println!("There is no draw to claim."),
+            },
+            "white human" | "white engine" | "black human" | "black engine" => {
+                let (color, player) = command.split_once(' ').unwrap();
+                let color = if color == "white" { Color::White } else { Color::Black };
+                let player = if player == "engine" { Player::Engine } else { Player::Human };
+                players.set(color, player);
+                println!("The {} now plays {}.", player, color);
+            },
+            _ => match play_typed_move(&mut chess_game, command) {
+                Ok(san) => {
+                    println!("Played {}.", san);
+                    show_board = true;
+                },
+                Err(error) => println!("{}", error),
+            },
+        }
+    }
+    log("INFO", "The program will exit now.");
+}
+
+// the board, then whose turn it is or how the game ended
+fn print_position(chess_game: &ChessGame) {
+    let chess_board = chess_game.borrow_board();
+    println!();
+    println!("{}", chess_board);
+    println!();
+    let outcome = chess_game.get_outcome();
+    if outcome.is_over() {
+        println!("{}.", outcome);
+    } else {
+        println!("It is {}'s turn.", chess_board.get_turn());
+        if let Some(draw) = chess_game.get_claimable_draw() {
+            println!("{} is possible; {} can claim it with \"draw\".", draw, chess_board.get_turn());
+        }
+    }
+}
+
+// the built-in engine's move, or else the external engine's
+fn get_engine_move(chess_game: &ChessGame, engine_limits: SearchLimits, external_engine: &mut Option<ExternalEngine>) -> Result<ChessMove, ChessError> {
+    let best_move = match external_engine {
+        None => {
+            let transposition_table = &mut TranspositionTable::default();
+            search(chess_game.borrow_board(), engine_limits, transposition_table, &AtomicBool::new(false), |_| ())
+        },
+        Some(external_engine) => {
+            external_engine.go(chess_game.borrow_start_board(), &chess_game.get_moves(), engine_limits)?;
+            // ends with an error once the engine is past its time, rather than waiting on it forever
+            loop {
+                if let Some(chess_move) = external_engine.receive_best_move(Duration::from_secs(1))? {
+                    break Some(chess_move);
+                }
+            }
+        }
+    };
+    return best_move.ok_or(ChessError::IllegalMove(String::from("The engine has no move to play.")));
+}
+
+// undo or redo a move; against the engine, its reply goes along with it so that it's the player's turn again
+fn step(chess_game: &mut ChessGame, players: Players, step_move: fn(&mut ChessGame) -> bool) -> bool {
+    if !step_move(chess_game) {
+        return false;
+    }
+    if players.get(chess_game.borrow_board().get_turn()) == Player::Engine {
+        step_move(chess_game);
+    }
+    return true;
+}
+
+// play a move typed as coordinates, or else in SAN; gives back the move in SAN
+fn play_typed_move(chess_game: &mut ChessGame, text: &str) -> Result<String, ChessError> {
+    let chess_board = chess_game.borrow_board();
+    let chess_move = match parse_coordinate(chess_board, text) {
+        Err(ChessError::Parse(_)) => parse_san(chess_board, text)?,
+        result => result?,
+    };
+    return play(chess_game, &chess_move);
+}
+
+fn play(chess_game: &mut ChessGame, chess_move: &ChessMove) -> Result<String, ChessError> {
+    let san = to_san(chess_game.borrow_board(), chess_move);
+    chess_game.play_move(chess_move.start, chess_move.target, chess_move.get_promotion())?;
+    return Ok(san);
+}
@@ -1,39 +1,76 @@
 // crate::utils::log
 
 use chrono::Local;
+use std::cell::Cell;
 use std::fs::File;
 use std::io::Write;
+use std::path::Path;
+use std::path::PathBuf;
+use std::sync::Mutex;
+use std::sync::atomic::AtomicBool;
+use std::sync::atomic::Ordering;
 
-const LOGPATH: &str = "logs/latest.log";
+use crate::error::ChessError;
 
-pub fn init_logs() {
-    let file_result = File::create(LOGPATH);
-    match file_result {
-        Ok(_) => {},
-        Err(_) => {
-            panic!("LOGERROR: Something went wrong while creating {}.", LOGPATH);
-        }
+pub const DEFAULT_LOG_PATH: &str = "logs/latest.log";
+
+// logs are only written to a file once init_logs has created it (e.g. not in tests)
+static LOG_PATH: Mutex<Option<PathBuf>> = Mutex::new(None);
+// whether logs are also printed to stdout (e.g. not when stdout is the game itself)
+static LOGS_ECHOED: AtomicBool = AtomicBool::new(true);
+
+thread_local! {
+    // nesting depth of with_logs_muted calls on this thread
+    static MUTE_DEPTH: Cell<u32> = const { Cell::new(0) };
+}
+
+// start a fresh log file at path, creating any missing directories on the way
+pub fn init_logs(path: &Path) -> Result<(), ChessError> {
+    let context = format!("Couldn't create {}", path.display());
+    if let Some(parent) = path.parent() && !parent.as_os_str().is_empty() {
+        std::fs::create_dir_all(parent).map_err(|error| ChessError::from(error).with_context(&context))?;
     }
+    File::create(path).map_err(|error| ChessError::from(error).with_context(&context))?;
+    *LOG_PATH.lock().unwrap() = Some(path.to_path_buf());
+    return Ok(());
+}
+
+pub fn set_logs_echoed(echoed: bool) {
+    LOGS_ECHOED.store(echoed, Ordering::Relaxed);
+}
+
+// run f without writing any logs (for e.g. scanning many candidate moves)
+pub fn with_logs_muted<T>(f: impl FnOnce() -> T) -> T {
+    MUTE_DEPTH.with(|depth| depth.set(depth.get() + 1));
+    let result = f();
+    MUTE_DEPTH.with(|depth| depth.set(depth.get() - 1));
+    result
 }
 
 pub fn log(label: impl Into<String>, msg: impl Into<String>) {
+    if MUTE_DEPTH.with(|depth| depth.get()) > 0 {
+        return;
+    }
     let label_ = label.into();
     let msg_ = msg.into();
     let datetime: String = format!("{}", Local::now().format("%Y-%m-%d %H:%M:%S"));
     let log:String = format!("{} [{}] {}", datetime, label_, msg_);
-    println!("{}", log);
-    let file_result = File::options().append(true).open(LOGPATH);
-    let mut log_file = match file_result {
-        Ok(_) => file_result.unwrap(),
-        Err(_) => {
-            panic!("LOGERROR: Something went wrong while opening {}.", LOGPATH);
-        }
+    if LOGS_ECHOED.load(Ordering::Relaxed) {
+        println!("{}", log);
+    }
+    let log_path = match LOG_PATH.lock().unwrap().clone() {
+        Some(log_path) => log_path,
+        None => return,
     };
-    let write_result = writeln!(&mut log_file, "{}", log);
-    match write_result {
-        Ok(_) => {},
-        Err(_) => {
-            panic!("LOGERROR: Something went wrong while appending to {}", LOGPATH);
+    // a failing log file shouldn't take the game down with it, so just report it
+    let mut log_file = match File::options().append(true).open(&log_path) {
+        Ok(log_file) => log_file,
+        Err(error) => {
+            eprintln!("LOGERROR: Something went wrong while opening {}: {}", log_path.display(), error);
+            return;
         }
+    };
+    if let Err(error) = writeln!(&mut log_file, "{}", log) {
+        eprintln!("LOGERROR: Something went wrong while appending to {}: {}", log_path.display(), error);
     }
 }
\ No newline at end of file
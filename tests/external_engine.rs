@@ -0,0 +1,82 @@
+// Plays against external UCI engines: the scripted stand-in through the chess binary, and chess-uci through the library
+
+use std::io::Write;
+use std::path::Path;
+use std::process::Command;
+use std::process::Stdio;
+use std::time::Duration;
+
+use chess::components::chess_board::ChessBoard;
+use chess::components::chess_game::ChessGame;
+use chess::engine::external::ExternalEngine;
+use chess::engine::search::MAX_DEPTH;
+use chess::engine::search::SearchLimits;
+use chess::notation::coordinate::to_coordinate;
+
+
+// run the binary in terminal mode against the scripted engine playing moves, typing input, and return what it printed
+fn play_scripted(moves: &str, input: &str, args: &[&str]) -> String {
+    let log_path = std::env::temp_dir().join(format!("chess-external-engine-test-{}.log", std::process::id()));
+    let mut child = Command::new(env!("CARGO_BIN_EXE_chess"))
+        .args(["--tui", "--opponent", "engine", "--engine-path", env!("CARGO_BIN_EXE_scripted-engine"), "--log"])
+        .arg(&log_path)
+        .args(args)
+        .env("SCRIPTED_ENGINE_MOVES", moves)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::null())
+        .spawn()
+        .unwrap();
+    child.stdin.take().unwrap().write_all(input.as_bytes()).unwrap();
+    let output = child.wait_with_output().unwrap();
+    assert!(output.status.success());
+    String::from_utf8(output.stdout).unwrap()
+}
+
+#[test]
+fn the_external_engine_answers_each_move() {
+    let output = play_scripted("e7e5 b8c6", "e4\nNf3\nfen\n", &[]);
+    assert!(output.contains("The engine played e5."));
+    assert!(output.contains("The engine played Nc6."));
+    assert!(output.contains("r1bqkbnr/pppp1ppp/2n5/4p3/4P3/5N2/PPPP1PPP/RNBQKB1R w KQkq - 2 3"));
+}
+
+#[test]
+fn a_bad_engine_move_hands_the_side_back() {
+    // e7e5 can't be played twice
+    let output = play_scripted("e7e5 e7e5", "e4\nNf3\nNc6\nfen\n", &[]);
+    assert!(output.contains("The engine played e5."));
+    assert!(output.contains("The engine Scripted engine played a bad move"));
+    assert!(output.contains("The human now plays black."));
+    assert!(output.contains("Played Nc6."));
+}
+
+#[test]
+fn an_engine_that_never_answers_hands_the_side_back() {
+    let output = play_scripted("-", "e4\ne5\n", &["--engine-time", "100"]);
+    assert!(output.contains("The engine Scripted engine didn't send its move in time."));
+    assert!(output.contains("The human now plays black."));
+    assert!(output.contains("Played e5."));
+}
+
+#[test]
+fn searches_through_the_chess_uci_engine() {
+    let mut external_engine = ExternalEngine::spawn(Path::new(env!("CARGO_BIN_EXE_chess-uci"))).unwrap();
+    assert!(external_engine.get_name().starts_with("chess"));
+    let mut chess_game = ChessGame::construct(ChessBoard::from_fen("4k3/8/8/3q4/8/8/3R4/4K3 w - - 0 1").unwrap());
+
+    // a cancelled search's move is skipped in favour of the next search's
+    external_engine.go(chess_game.borrow_start_board(), &[], SearchLimits { max_depth: MAX_DEPTH, time_limit: None }).unwrap();
+    external_engine.cancel();
+    external_engine.go(chess_game.borrow_start_board(), &[], SearchLimits { max_depth: 2, time_limit: None }).unwrap();
+    let chess_move = external_engine.receive_best_move(Duration::from_secs(10)).unwrap().unwrap();
+    assert_eq!(to_coordinate(&chess_move), "d2d5");
+
+    // the position is sent as the start position and the moves since
+    chess_game.play_move(chess_move.start, chess_move.target, None).unwrap();
+    external_engine.go(chess_game.borrow_start_board(), &chess_game.get_moves(), SearchLimits { max_depth: 1, time_limit: None }).unwrap();
+    let chess_move = external_engine.receive_best_move(Duration::from_secs(10)).unwrap().unwrap();
+    assert!(chess_game.play_move(chess_move.start, chess_move.target, None).is_ok());
+
+    assert!(ExternalEngine::spawn(Path::new("no/such/engine")).is_err());
+}
@@ -0,0 +1,63 @@
+// Plays scripted games through the terminal mode of the chess binary
+
+use std::io::Write;
+use std::process::Command;
+use std::process::Stdio;
+
+
+// run the binary in terminal mode with the given extra arguments, typing input, and return what it printed
+fn play(args: &[&str], input: &str) -> String {
+    let log_path = std::env::temp_dir().join(format!("chess-terminal-test-{}.log", std::process::id()));
+    let mut child = Command::new(env!("CARGO_BIN_EXE_chess"))
+        .arg("--tui")
+        .arg("--log")
+        .arg(&log_path)
+        .args(args)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::null())
+        .spawn()
+        .unwrap();
+    child.stdin.take().unwrap().write_all(input.as_bytes()).unwrap();
+    let output = child.wait_with_output().unwrap();
+    assert!(output.status.success());
+    String::from_utf8(output.stdout).unwrap()
+}
+
+#[test]
+fn plays_a_game_to_checkmate_in_mixed_notation() {
+    let output = play(&[], "e4\ne7e5\nQh5\nNc6\nBc4\nb8c6\nNf6\nQxf7\n");
+    assert!(output.contains("Played Nc6."));
+    assert!(output.contains("\"b8c6\" is not a legal move here."));
+    assert!(output.contains("Played Qxf7#."));
+    assert!(output.contains("Checkmate: white wins."));
+}
+
+#[test]
+fn undoes_moves_and_prints_the_position() {
+    let output = play(&["8/8/8/8/8/8/k6P/7K w - - 0 1"], "h4\nundo\nundo\nh2h3\nfen\nquit\nh4\n");
+    assert!(output.contains("There is no move to undo."));
+    assert!(output.contains("8/8/8/8/8/7P/k7/7K b - - 0 1"));
+    // nothing after quit is played
+    assert_eq!(output.matches("Played").count(), 2);
+}
+
+#[test]
+fn the_engine_answers_each_move() {
+    // black's queen hangs to the rook, which the engine takes
+    let output = play(&["--opponent", "engine", "--side", "black", "--engine-depth", "2", "--fen", "4k3/8/8/8/3q4/8/3R4/4K3 b - - 0 1"], "Qd5
+undo
+fen
+");
+    assert!(output.contains("The engine played Rxd5."));
+    assert!(output.contains("4k3/8/8/8/3q4/8/3R4/4K3 b - - 0 1"));
+}
+
+#[test]
+fn a_repeated_position_can_be_claimed_as_a_draw() {
+    let output = play(&[], "draw\nNf3\nNf6\nNg1\nNg8\nNf3\nNf6\nNg1\nNg8\ndraw\npgn\n");
+    assert!(output.contains("There is no draw to claim."));
+    assert!(output.contains("Draw by threefold repetition is possible; white can claim it with \"draw\"."));
+    assert!(output.contains("Draw by threefold repetition."));
+    assert!(output.contains("4. Ng1 Ng8 1/2-1/2"));
+}
@@ -0,0 +1,94 @@
+// Talks to the chess-uci binary the way a chess GUI would
+
+use std::io::BufRead;
+use std::io::BufReader;
+use std::io::Write;
+use std::process::Child;
+use std::process::ChildStdout;
+use std::process::Command;
+use std::process::Stdio;
+
+
+fn start_engine() -> (Child, BufReader<ChildStdout>) {
+    let mut child = Command::new(env!("CARGO_BIN_EXE_chess-uci"))
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .spawn()
+        .unwrap();
+    let stdout = BufReader::new(child.stdout.take().unwrap());
+    (child, stdout)
+}
+
+fn send(child: &mut Child, command: &str) {
+    writeln!(child.stdin.as_mut().unwrap(), "{}", command).unwrap();
+}
+
+// the lines the engine sends up to and including the first one starting with prefix
+fn read_until(stdout: &mut BufReader<ChildStdout>, prefix: &str) -> Vec<String> {
+    let mut lines = vec![];
+    for line in stdout.lines() {
+        let line = line.unwrap();
+        let is_done = line.starts_with(prefix);
+        lines.push(line);
+        if is_done {
+            return lines;
+        }
+    }
+    panic!("The engine stopped before sending \"{}\": {:?}", prefix, lines);
+}
+
+#[test]
+fn handshakes_and_exposes_its_options() {
+    let (mut child, mut stdout) = start_engine();
+    send(&mut child, "uci");
+    let lines = read_until(&mut stdout, "uciok");
+    assert!(lines.iter().any(|line| line.starts_with("id name")));
+    assert!(lines.iter().any(|line| line.starts_with("option name Hash type spin")));
+    send(&mut child, "setoption name Hash value 32");
+    send(&mut child, "isready");
+    let lines = read_until(&mut stdout, "readyok");
+    assert_eq!(lines[0], "info string Hash set to 32 MB.");
+
+    // a malformed command is answered, not fatal
+    send(&mut child, "setoption value 5");
+    send(&mut child, "setoption");
+    send(&mut child, "isready");
+    let lines = read_until(&mut stdout, "readyok");
+    assert_eq!(lines.len(), 3);
+    assert!(lines[..2].iter().all(|line| line.starts_with("info string setoption needs")));
+    send(&mut child, "quit");
+    assert!(child.wait().unwrap().success());
+}
+
+#[test]
+fn searches_positions_with_moves_to_a_depth() {
+    let (mut child, mut stdout) = start_engine();
+    // after 1. f3 e5 2. g4, black mates with Qh4
+    send(&mut child, "position startpos moves f2f3 e7e5 g2g4");
+    send(&mut child, "go depth 2");
+    let lines = read_until(&mut stdout, "bestmove");
+    assert!(lines[0].starts_with("info depth 1 score mate 1 nodes"));
+    assert_eq!(lines.last().unwrap(), "bestmove d8h4");
+
+    // the white rook takes the hanging queen
+    send(&mut child, "position fen 4k3/8/8/3q4/8/8/3R4/4K3 w - - 0 1");
+    send(&mut child, "go wtime 10000 btime 10000 winc 100 binc 100");
+    assert_eq!(read_until(&mut stdout, "bestmove").last().unwrap(), "bestmove d2d5");
+    send(&mut child, "quit");
+    assert!(child.wait().unwrap().success());
+}
+
+#[test]
+fn stops_an_infinite_search_with_its_best_move() {
+    let (mut child, mut stdout) = start_engine();
+    send(&mut child, "position startpos");
+    send(&mut child, "go infinite");
+    send(&mut child, "stop");
+    let lines = read_until(&mut stdout, "bestmove");
+    assert!(lines.last().unwrap().len() >= "bestmove e2e4".len());
+    send(&mut child, "position startpos moves e2e5");
+    send(&mut child, "isready");
+    assert_eq!(read_until(&mut stdout, "readyok")[0], "info string \"e2e5\" is not a legal move here.");
+    send(&mut child, "quit");
+    assert!(child.wait().unwrap().success());
+}